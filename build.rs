@@ -12,6 +12,46 @@ fn main() -> anyhow::Result<()> {
             #[serde(rename_all = "camelCase")]
             "#,
         )
+        .type_attribute(
+            "user.UserInfo",
+            r#"
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize
+            )]
+            #[serde(rename_all = "camelCase")]
+            "#,
+        )
+        .type_attribute(
+            "user.LoginRecord",
+            r#"
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize
+            )]
+            #[serde(rename_all = "camelCase")]
+            "#,
+        )
+        .type_attribute(
+            "user.ListLoginHistoryResponse",
+            r#"
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize
+            )]
+            #[serde(rename_all = "camelCase")]
+            "#,
+        )
+        .type_attribute(
+            "user.ListUsersResponse",
+            r#"
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize
+            )]
+            #[serde(rename_all = "camelCase")]
+            "#,
+        )
         .type_attribute(
             "user.UserExistsResponse",
             r#"
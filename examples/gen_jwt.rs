@@ -1,7 +1,6 @@
-use user_server::middlewares::auth::{
-    identity::Identity,
-    jwt::{JWT, JwtConfig},
-    principal::Principal,
+use user_server::{
+    conf::jwt::JwtConfig,
+    middlewares::auth::{identity::Identity, jwt::JWT, principal::Principal},
 };
 
 fn main() -> anyhow::Result<()> {
@@ -10,7 +9,7 @@ fn main() -> anyhow::Result<()> {
         username: "1234567890".to_string(),
         identity: Identity::Admin,
     };
-    let jwt = JWT::new(JwtConfig::default());
+    let jwt = JWT::new(&JwtConfig::default())?;
     let token = jwt.encode(principal).unwrap();
     println!("access_token: {token}");
     let principal = jwt.decode(&token).unwrap();
@@ -0,0 +1,71 @@
+use clap::Parser;
+use config::{Config, File, FileFormat};
+use user_server::{
+    conf::jwt::{JwtConfig, SigningKeyConfig},
+    middlewares::auth::jwt::JWT,
+    utils::crypto::generate_token,
+};
+
+/// 轮换 JWT 签名密钥
+///
+/// 读取配置文件中的 jwt 配置，把当前的签名密钥移动到 previous_keys 中，
+/// 输出轮换后的 jwt 配置（JSON 格式，同时也是合法的 YAML），替换配置文件中的 jwt 段后重启服务即可。
+///
+/// cargo run --example rotate_jwt_key -- --config prod.yml
+/// cargo run --example rotate_jwt_key -- --config prod.yml --algorithm ES256 \
+///     --private-key file:/app/conf/jwt_private.pem --public-key file:/app/conf/jwt_public.pem
+#[derive(Parser)]
+struct RotateOpts {
+    /// 配置文件路径
+    #[clap(long)]
+    config: String,
+    /// 新密钥的签名算法，默认与当前密钥相同
+    #[clap(long)]
+    algorithm: Option<String>,
+    /// 新密钥的 kid，默认随机生成
+    #[clap(long)]
+    kid: Option<String>,
+    /// HMAC 算法的新密钥，默认随机生成
+    #[clap(long)]
+    secret: Option<String>,
+    /// 非对称算法的新私钥
+    #[clap(long)]
+    private_key: Option<String>,
+    /// 非对称算法的新公钥
+    #[clap(long)]
+    public_key: Option<String>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct JwtSection {
+    #[serde(default)]
+    jwt: JwtConfig,
+}
+
+fn main() -> anyhow::Result<()> {
+    let opts = RotateOpts::parse();
+    let section: JwtSection = Config::builder()
+        .add_source(File::with_name(&opts.config).format(FileFormat::Yaml))
+        .build()?
+        .try_deserialize()?;
+    let algorithm = opts
+        .algorithm
+        .unwrap_or_else(|| section.jwt.algorithm().to_string());
+    let secret = algorithm
+        .starts_with("HS")
+        .then(|| opts.secret.unwrap_or_else(generate_token));
+    let next = SigningKeyConfig {
+        algorithm,
+        kid: Some(opts.kid.unwrap_or_else(|| xid::new().to_string())),
+        secret,
+        private_key: opts.private_key,
+        public_key: opts.public_key,
+    };
+    let jwt = section
+        .jwt
+        .rotate(next, jsonwebtoken::get_current_timestamp());
+    // 确认轮换后的配置可以正常加载
+    JWT::new(&jwt)?;
+    println!("{}", serde_json::to_string_pretty(&JwtSection { jwt })?);
+    Ok(())
+}
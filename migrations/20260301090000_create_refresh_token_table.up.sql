@@ -0,0 +1,29 @@
+-- Add up migration script here
+-- 创建 refresh_token 表
+CREATE TABLE IF NOT EXISTS refresh_token (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    family_id VARCHAR(32) NOT NULL,
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ NULL,
+    revoked_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 添加表注释
+COMMENT ON TABLE refresh_token IS '刷新令牌表';
+
+-- 添加列注释
+COMMENT ON COLUMN refresh_token.id IS '令牌ID';
+COMMENT ON COLUMN refresh_token.user_id IS '所属用户ID';
+COMMENT ON COLUMN refresh_token.family_id IS '令牌族ID，同一次登录轮换出的令牌属于同一族';
+COMMENT ON COLUMN refresh_token.token_hash IS '令牌的 SHA-256 摘要';
+COMMENT ON COLUMN refresh_token.expires_at IS '过期时间';
+COMMENT ON COLUMN refresh_token.used_at IS '被轮换使用的时间';
+COMMENT ON COLUMN refresh_token.revoked_at IS '被吊销的时间';
+COMMENT ON COLUMN refresh_token.created_at IS '创建时间';
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_refresh_token_user_id ON refresh_token(user_id);
+CREATE INDEX IF NOT EXISTS idx_refresh_token_family_id ON refresh_token(family_id);
@@ -0,0 +1,28 @@
+-- Add up migration script here
+-- 创建 login_history 表
+CREATE TABLE IF NOT EXISTS login_history (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    username VARCHAR(50) NOT NULL,
+    success BOOLEAN NOT NULL,
+    reason VARCHAR(32) NULL,
+    ip INET NULL,
+    user_agent VARCHAR(512) NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 添加表注释
+COMMENT ON TABLE login_history IS '登录历史表';
+
+-- 添加列注释
+COMMENT ON COLUMN login_history.id IS '记录ID';
+COMMENT ON COLUMN login_history.user_id IS '用户ID，用户名不存在时为空';
+COMMENT ON COLUMN login_history.username IS '登录时使用的用户名';
+COMMENT ON COLUMN login_history.success IS '是否登录成功';
+COMMENT ON COLUMN login_history.reason IS '失败原因：unknown_user, bad_password, disabled, locked';
+COMMENT ON COLUMN login_history.ip IS '客户端IP';
+COMMENT ON COLUMN login_history.user_agent IS '客户端 User-Agent';
+COMMENT ON COLUMN login_history.created_at IS '登录时间';
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_login_history_user_id_created_at ON login_history(user_id, created_at DESC);
@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- 创建 password_reset_token 表
+CREATE TABLE IF NOT EXISTS password_reset_token (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 添加表注释
+COMMENT ON TABLE password_reset_token IS '重置密码令牌表';
+
+-- 添加列注释
+COMMENT ON COLUMN password_reset_token.id IS '令牌ID';
+COMMENT ON COLUMN password_reset_token.user_id IS '所属用户ID';
+COMMENT ON COLUMN password_reset_token.token_hash IS '令牌的 SHA-256 摘要';
+COMMENT ON COLUMN password_reset_token.expires_at IS '过期时间';
+COMMENT ON COLUMN password_reset_token.used_at IS '使用或作废的时间，令牌只能使用一次';
+COMMENT ON COLUMN password_reset_token.created_at IS '创建时间';
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_password_reset_token_user_id ON password_reset_token(user_id);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS email_verification_token;
+DROP INDEX IF EXISTS idx_user_email_unique;
+CREATE INDEX IF NOT EXISTS idx_user_email ON "user"(email);
+ALTER TABLE "user" DROP COLUMN IF EXISTS email_verified_at;
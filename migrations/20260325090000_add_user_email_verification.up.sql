@@ -0,0 +1,34 @@
+-- Add up migration script here
+-- 邮箱验证时间，NULL 表示尚未验证
+ALTER TABLE "user" ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMPTZ NULL;
+COMMENT ON COLUMN "user".email_verified_at IS '邮箱验证时间，NULL 表示尚未验证';
+
+-- 邮箱不区分大小写唯一
+DROP INDEX IF EXISTS idx_user_email;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_email_unique ON "user"(LOWER(email));
+
+-- 创建 email_verification_token 表
+CREATE TABLE IF NOT EXISTS email_verification_token (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    email VARCHAR(100) NOT NULL,
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 添加表注释
+COMMENT ON TABLE email_verification_token IS '邮箱验证令牌表';
+
+-- 添加列注释
+COMMENT ON COLUMN email_verification_token.id IS '令牌ID';
+COMMENT ON COLUMN email_verification_token.user_id IS '所属用户ID';
+COMMENT ON COLUMN email_verification_token.email IS '待验证的邮箱，与用户当前邮箱一致时才能验证';
+COMMENT ON COLUMN email_verification_token.token_hash IS '令牌的 SHA-256 摘要';
+COMMENT ON COLUMN email_verification_token.expires_at IS '过期时间';
+COMMENT ON COLUMN email_verification_token.used_at IS '使用或作废的时间，令牌只能使用一次';
+COMMENT ON COLUMN email_verification_token.created_at IS '创建时间';
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_email_verification_token_user_id ON email_verification_token(user_id);
@@ -1,14 +1,18 @@
 use std::net::SocketAddr;
 
-use axum::{
-    extract::{DefaultBodyLimit, Request},
-    http::StatusCode,
-};
+use axum::{extract::DefaultBodyLimit, http::StatusCode, middleware};
 use bytesize::ByteSize;
 use tower_http::{normalize_path::NormalizePathLayer, timeout::TimeoutLayer, trace::TraceLayer};
 
 use crate::{
-    conf, middlewares, router, state::app_state::AppState, utils::latency::LatencyOnResponse,
+    conf,
+    middlewares::{
+        self, metrics::http_metrics::track_http_metrics,
+        request_id::http_request_id::set_request_id,
+    },
+    router,
+    state::app_state::AppState,
+    utils::{latency::LatencyOnResponse, trace::make_http_span},
 };
 
 /// 服务端配置信息
@@ -28,6 +32,11 @@ impl Server {
         // client::set_global_grpc_client_pool(grpc_addr, 10).await?;
         // new app state 创建 app 数据状态对象
         let app_state = AppState::new(grpc_addr).await?;
+        // 开启指标时安装 Prometheus 记录器，并单独监听一个端口暴露 /metrics
+        if self.server_config.metrics().enabled() {
+            crate::metrics::init_metrics()?;
+            router::metrics::serve_metrics(self.server_config.metrics().http_port()).await?;
+        }
         // create our application router 创建路由
         let app_router = self.build_router(app_state).await;
         // use axum to serve our application, listening on the specified address
@@ -66,13 +75,9 @@ impl Server {
         // cors layer setting 跨域中间件
         let cors_layer = middlewares::cors::app_cors::app_cors();
 
+        // 请求头带有 traceparent 时继续上游的链路
         let tracing = TraceLayer::new_for_http()
-            .make_span_with(|request: &Request<axum::body::Body>| {
-                let method = request.method();
-                let path = request.uri().path();
-                let id = xid::new();
-                tracing::info_span!("Api Request", id = %id, method = %method, path = %path)
-            })
+            .make_span_with(make_http_span)
             .on_request(())
             .on_failure(())
             .on_response(LatencyOnResponse);
@@ -83,18 +88,24 @@ impl Server {
         // return the router 返回路由
         axum::Router::new()
             .nest("/api/v1", router::merge_router())
+            .merge(router::jwks::get_jwks_router())
+            .merge(router::health::get_health_router())
             .layer(timeout)
+            // 统计全部请求，超时的请求也会计入
+            .layer(middleware::from_fn(track_http_metrics))
             .layer(body_size_limit)
             .layer(tracing)
             .layer(cors_layer)
             .layer(normalize_path)
+            // 最外层设置 x-request-id，日志 span 和错误返回都会用到
+            .layer(middleware::from_fn(set_request_id))
             .with_state(state)
     }
 }
 
 // 处理打断信号，优雅关闭服务
 // 中断信号处理，这个不能处理子任务中的耗时任务。
-async fn shutdown_signal() {
+pub async fn shutdown_signal() {
     // 监听 Ctrl + c
     let ctrl_c = async {
         tokio::signal::ctrl_c()
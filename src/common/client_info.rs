@@ -0,0 +1,189 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+
+/// 转发客户端 IP 使用的 metadata
+pub const CLIENT_IP_METADATA: &str = "x-forwarded-for";
+/// 转发客户端 User-Agent 使用的 metadata，gRPC 请求自身的 user-agent 会被 tonic 改写
+pub const CLIENT_USER_AGENT_METADATA: &str = "x-forwarded-user-agent";
+/// User-Agent 的最大保存长度
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// 全局的可信代理列表
+static TRUSTED_PROXIES: OnceLock<TrustedProxies> = OnceLock::new();
+
+/// 可信代理，只有来自这些地址的调用才能通过 metadata 转发客户端 IP
+///
+/// # 功能描述
+/// 每一项是一个 IP（如 `10.0.0.5`）或网段（如 `172.18.0.0/16`）。
+/// 列表为空时不信任任何转发的 IP，一律使用连接的对端地址。
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Vec<(IpAddr, u8)>,
+}
+
+impl TrustedProxies {
+    /// 解析可信代理列表
+    ///
+    /// # 参数
+    /// - `entries`: IP 或 CIDR 网段
+    ///
+    /// # 返回值
+    /// 有无法解析的项时返回错误
+    pub fn parse<S: AsRef<str>>(entries: &[S]) -> anyhow::Result<Self> {
+        let networks = entries
+            .iter()
+            .map(|entry| {
+                let entry = entry.as_ref().trim();
+                let (ip, prefix) = match entry.split_once('/') {
+                    Some((ip, prefix)) => (ip, Some(prefix)),
+                    None => (entry, None),
+                };
+                let ip = IpAddr::from_str(ip)
+                    .map_err(|_| anyhow::anyhow!("无效的可信代理地址：{}", entry))?;
+                let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+                let prefix = match prefix {
+                    Some(prefix) => prefix
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|prefix| *prefix <= max_prefix)
+                        .ok_or_else(|| anyhow::anyhow!("无效的可信代理网段：{}", entry))?,
+                    None => max_prefix,
+                };
+                Ok((ip, prefix))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { networks })
+    }
+
+    /// 地址是否属于可信代理
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.networks
+            .iter()
+            .any(|(network, prefix)| in_network(ip, *network, *prefix))
+    }
+}
+
+/// 判断地址是否在网段内，IPv4 和 IPv6 不互相匹配
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// 初始化全局的可信代理列表，gRPC 服务启动时调用
+pub fn init_trusted_proxies<S: AsRef<str>>(entries: &[S]) -> anyhow::Result<()> {
+    let trusted = TrustedProxies::parse(entries)?;
+    TRUSTED_PROXIES
+        .set(trusted)
+        .map_err(|_| anyhow::anyhow!("可信代理列表已经初始化"))
+}
+
+/// 获取全局的可信代理列表，没有初始化时为空，不信任任何转发的 IP
+pub fn get_trusted_proxies() -> &'static TrustedProxies {
+    TRUSTED_PROXIES.get_or_init(TrustedProxies::default)
+}
+
+/// 客户端信息
+///
+/// # 功能描述
+/// HTTP 服务从连接地址和 User-Agent 请求头中提取，通过 metadata 转发给 gRPC 服务，
+/// 供登录限流、登录历史等需要识别客户端的功能使用。
+///
+/// # 成员
+/// - ip: 客户端 IP
+/// - user_agent: 客户端 User-Agent
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    /// 把客户端信息写入 gRPC 请求的 metadata
+    pub fn forward<T>(&self, request: &mut tonic::Request<T>) {
+        let metadata = request.metadata_mut();
+        if let Some(value) = self.ip.and_then(|ip| ip.to_string().parse().ok()) {
+            metadata.insert(CLIENT_IP_METADATA, value);
+        }
+        if let Some(value) = self
+            .user_agent
+            .as_deref()
+            .and_then(|user_agent| user_agent.parse().ok())
+        {
+            metadata.insert(CLIENT_USER_AGENT_METADATA, value);
+        }
+    }
+
+    /// 从 gRPC 请求中读取客户端信息，使用全局的可信代理列表
+    pub fn from_grpc<T>(request: &tonic::Request<T>) -> Self {
+        Self::from_grpc_with(request, get_trusted_proxies())
+    }
+
+    /// 从 gRPC 请求中读取客户端信息
+    ///
+    /// # 功能描述
+    /// 只有对端地址属于可信代理（即 HTTP 网关）时才使用 metadata 中转发的客户端 IP，
+    /// 直接调用 gRPC 的请求无法伪造 IP 绕过按 IP 的登录限制。
+    /// 转发的 IP 缺失或无法解析时使用连接的对端地址。
+    ///
+    /// # 参数
+    /// - `request`: gRPC 请求
+    /// - `trusted`: 可信代理列表
+    pub fn from_grpc_with<T>(request: &tonic::Request<T>, trusted: &TrustedProxies) -> Self {
+        let metadata = request.metadata();
+        let peer = request.remote_addr().map(|addr| addr.ip().to_canonical());
+        let forwarded = metadata
+            .get(CLIENT_IP_METADATA)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .and_then(|ip| IpAddr::from_str(ip.trim()).ok());
+        let ip = match peer {
+            Some(peer) if trusted.contains(peer) => forwarded.or(Some(peer)),
+            peer => peer,
+        };
+        let user_agent = metadata
+            .get(CLIENT_USER_AGENT_METADATA)
+            .and_then(|value| value.to_str().ok())
+            .map(|user_agent| user_agent.chars().take(MAX_USER_AGENT_LEN).collect());
+        Self { ip, user_agent }
+    }
+}
+
+/// 从 HTTP 请求中提取客户端信息
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_canonical());
+        let user_agent = parts
+            .headers
+            .get(axum::http::header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+        Ok(Self { ip, user_agent })
+    }
+}
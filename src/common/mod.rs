@@ -1,3 +1,4 @@
+pub mod client_info;
 pub mod json;
 pub mod path;
 pub mod query;
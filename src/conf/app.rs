@@ -1,4 +1,13 @@
+use crate::conf::enumeration::EnumerationConfig;
 use crate::conf::grpc::GrpcConfig;
+use crate::conf::jwt::JwtConfig;
+use crate::conf::login_limit::LoginLimitConfig;
+use crate::conf::mail::MailConfig;
+use crate::conf::metrics::MetricsConfig;
+use crate::conf::otel::OtelConfig;
+use crate::conf::password_hash::PasswordHashConfig;
+use crate::conf::password_policy::PasswordPolicyConfig;
+use crate::conf::register::RegisterConfig;
 use crate::conf::{database::DbConfig, http::HttpConfig};
 
 use crate::conf::redis::RedisConfig;
@@ -27,6 +36,24 @@ pub struct AppConfig {
     grpc: GrpcConfig,
     database: DbConfig,
     redis: RedisConfig,
+    #[serde(default)]
+    jwt: JwtConfig,
+    #[serde(default)]
+    login_limit: LoginLimitConfig,
+    #[serde(default)]
+    mail: MailConfig,
+    #[serde(default)]
+    register: RegisterConfig,
+    #[serde(default)]
+    password_hash: PasswordHashConfig,
+    #[serde(default)]
+    password_policy: PasswordPolicyConfig,
+    #[serde(default)]
+    enumeration: EnumerationConfig,
+    #[serde(default)]
+    metrics: MetricsConfig,
+    #[serde(default)]
+    otel: OtelConfig,
     is_dev: bool,
 }
 impl AppConfig {
@@ -82,6 +109,33 @@ impl AppConfig {
     pub fn redis(&self) -> &RedisConfig {
         &self.redis
     }
+    pub fn jwt(&self) -> &JwtConfig {
+        &self.jwt
+    }
+    pub fn login_limit(&self) -> &LoginLimitConfig {
+        &self.login_limit
+    }
+    pub fn mail(&self) -> &MailConfig {
+        &self.mail
+    }
+    pub fn register(&self) -> &RegisterConfig {
+        &self.register
+    }
+    pub fn password_hash(&self) -> &PasswordHashConfig {
+        &self.password_hash
+    }
+    pub fn password_policy(&self) -> &PasswordPolicyConfig {
+        &self.password_policy
+    }
+    pub fn enumeration(&self) -> &EnumerationConfig {
+        &self.enumeration
+    }
+    pub fn metrics(&self) -> &MetricsConfig {
+        &self.metrics
+    }
+    pub fn otel(&self) -> &OtelConfig {
+        &self.otel
+    }
     pub fn is_dev(&self) -> bool {
         self.is_dev
     }
@@ -0,0 +1,21 @@
+/// 防止枚举用户名的配置
+///
+/// - hardened: 是否启用加固模式。启用后登录时未知用户同样执行一次密码哈希验证，
+///   密码正确后才提示账号被禁用，注册冲突时不区分是用户名还是邮箱已被使用
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct EnumerationConfig {
+    hardened: bool,
+}
+
+impl Default for EnumerationConfig {
+    fn default() -> Self {
+        Self { hardened: true }
+    }
+}
+
+impl EnumerationConfig {
+    pub fn hardened(&self) -> bool {
+        self.hardened
+    }
+}
@@ -1,9 +1,13 @@
 /// 服务端口和日志信息相关配置
+///
+/// - trusted_proxies: 可信代理的 IP 或网段，只有来自这些地址的调用才能转发客户端 IP
 #[derive(Debug, serde::Deserialize)]
 pub struct GrpcConfig {
     name: String,
     port: u16,
     log_level: String,
+    #[serde(default)]
+    trusted_proxies: Vec<String>,
 }
 
 impl GrpcConfig {
@@ -16,4 +20,7 @@ impl GrpcConfig {
     pub fn log_level(&self) -> &str {
         &self.log_level
     }
+    pub fn trusted_proxies(&self) -> &[String] {
+        &self.trusted_proxies
+    }
 }
@@ -0,0 +1,192 @@
+/// 默认的签名密钥，只允许在开发环境中使用
+pub const DEFAULT_SECRET: &str = "WK2953aOagwo2SwUye";
+/// access token 默认有效期 15 分钟
+const EXPIRATION_SECS: u64 = 60 * 15;
+/// refresh token 默认有效期 30 天
+const REFRESH_EXPIRATION_SECS: u64 = 60 * 60 * 24 * 30;
+
+/// JWT 签发和校验相关配置
+///
+/// - algorithm: 签名算法，HS256/HS384/HS512 使用 secret，RS*/PS*/ES256/ES384/EdDSA 使用 PEM 密钥
+/// - kid: 写入 token header 的密钥 id，非对称算法未配置时根据公钥自动生成
+/// - private_key: PEM 格式的私钥，未配置时只能校验不能签发
+/// - public_key: PEM 格式的公钥，非对称算法必须配置
+///
+/// - previous_keys: 轮换下来的旧密钥，只用于校验，按 kid 选择
+///
+/// secret、private_key、public_key 支持 `file:<path>` 的形式从文件读取
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct JwtConfig {
+    algorithm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+    secret: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    private_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+    issuer: String,
+    audience: String,
+    expiration_secs: u64,
+    refresh_expiration_secs: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    previous_keys: Vec<JwtKeyConfig>,
+}
+
+/// 只用于校验的旧密钥
+///
+/// - kid: 密钥 id，与 token header 中的 kid 对应，非对称算法未配置时根据公钥自动生成
+/// - algorithm: 签名算法
+/// - secret: HMAC 算法使用的密钥
+/// - public_key: 非对称算法使用的 PEM 公钥
+/// - expires_at: 过期时间（Unix 时间戳，秒），过期后不再接受该密钥签发的 token
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct JwtKeyConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+    algorithm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+}
+
+/// 轮换后新的签名密钥
+///
+/// HMAC 算法需要 secret，非对称算法需要 private_key 和 public_key
+#[derive(Debug, Default)]
+pub struct SigningKeyConfig {
+    pub algorithm: String,
+    pub kid: Option<String>,
+    pub secret: Option<String>,
+    pub private_key: Option<String>,
+    pub public_key: Option<String>,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: "HS256".into(),
+            kid: None,
+            secret: DEFAULT_SECRET.into(),
+            private_key: None,
+            public_key: None,
+            issuer: "issuer".into(),
+            audience: "audience".into(),
+            expiration_secs: EXPIRATION_SECS,
+            refresh_expiration_secs: REFRESH_EXPIRATION_SECS,
+            previous_keys: Vec::new(),
+        }
+    }
+}
+
+impl Default for JwtKeyConfig {
+    fn default() -> Self {
+        Self {
+            kid: None,
+            algorithm: "HS256".into(),
+            secret: None,
+            public_key: None,
+            expires_at: None,
+        }
+    }
+}
+
+impl JwtConfig {
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+    pub fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+    pub fn private_key(&self) -> Option<&str> {
+        self.private_key.as_deref()
+    }
+    pub fn public_key(&self) -> Option<&str> {
+        self.public_key.as_deref()
+    }
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+    pub fn audience(&self) -> &str {
+        &self.audience
+    }
+    pub fn expiration_secs(&self) -> u64 {
+        self.expiration_secs
+    }
+    pub fn refresh_expiration_secs(&self) -> u64 {
+        self.refresh_expiration_secs
+    }
+    pub fn previous_keys(&self) -> &[JwtKeyConfig] {
+        &self.previous_keys
+    }
+
+    /// 轮换签名密钥
+    ///
+    /// # 功能描述
+    /// 当前的签名密钥降级为只用于校验的旧密钥，过期时间为 `now` 加上 access token 的有效期，
+    /// 届时它签发的 token 都已经过期；已经过期的旧密钥会被清理掉。
+    /// 新密钥成为当前的签名密钥，之后签发的 token 都使用新密钥。
+    ///
+    /// # 参数
+    /// - `next`: 新的签名密钥
+    /// - `now`: 当前时间（Unix 时间戳，秒）
+    ///
+    /// # 返回值
+    /// 返回轮换后的配置
+    pub fn rotate(&self, next: SigningKeyConfig, now: u64) -> Self {
+        let is_hmac = self.algorithm.starts_with("HS");
+        let retired = JwtKeyConfig {
+            kid: self.kid.clone(),
+            algorithm: self.algorithm.clone(),
+            secret: is_hmac.then(|| self.secret.clone()),
+            public_key: if is_hmac {
+                None
+            } else {
+                self.public_key.clone()
+            },
+            expires_at: Some(now.saturating_add(self.expiration_secs)),
+        };
+        let mut previous_keys = vec![retired];
+        previous_keys.extend(
+            self.previous_keys
+                .iter()
+                .filter(|key| key.expires_at.is_none_or(|expires_at| expires_at > now))
+                .cloned(),
+        );
+        Self {
+            algorithm: next.algorithm,
+            kid: next.kid,
+            secret: next.secret.unwrap_or_default(),
+            private_key: next.private_key,
+            public_key: next.public_key,
+            previous_keys,
+            ..self.clone()
+        }
+    }
+}
+
+impl JwtKeyConfig {
+    pub fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+    pub fn secret(&self) -> Option<&str> {
+        self.secret.as_deref()
+    }
+    pub fn public_key(&self) -> Option<&str> {
+        self.public_key.as_deref()
+    }
+    pub fn expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
+}
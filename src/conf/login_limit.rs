@@ -0,0 +1,60 @@
+/// 登录限流相关配置
+///
+/// - max_user_failures: 同一个用户名在统计窗口内允许的连续失败次数
+/// - max_ip_failures: 同一个 IP 在统计窗口内允许的失败次数
+/// - failure_window_secs: 失败次数的统计窗口
+/// - base_lockout_secs: 第一次锁定的时长，之后每次锁定时长翻倍
+/// - max_lockout_secs: 锁定时长的上限
+/// - lockout_reset_secs: 锁定次数的保留时间，超过后锁定时长从头计算
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct LoginLimitConfig {
+    max_user_failures: u64,
+    max_ip_failures: u64,
+    failure_window_secs: u64,
+    base_lockout_secs: u64,
+    max_lockout_secs: u64,
+    lockout_reset_secs: u64,
+}
+
+impl Default for LoginLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_user_failures: 5,
+            max_ip_failures: 20,
+            failure_window_secs: 60 * 15,
+            base_lockout_secs: 60,
+            max_lockout_secs: 60 * 60,
+            lockout_reset_secs: 60 * 60 * 24,
+        }
+    }
+}
+
+impl LoginLimitConfig {
+    pub fn max_user_failures(&self) -> u64 {
+        self.max_user_failures
+    }
+    pub fn max_ip_failures(&self) -> u64 {
+        self.max_ip_failures
+    }
+    pub fn failure_window_secs(&self) -> u64 {
+        self.failure_window_secs
+    }
+    pub fn lockout_reset_secs(&self) -> u64 {
+        self.lockout_reset_secs
+    }
+
+    /// 第 `count` 次锁定的时长（指数退避）
+    ///
+    /// # 参数
+    /// - `count`: 锁定次数，从 1 开始
+    ///
+    /// # 返回值
+    /// 返回锁定时长（秒），不超过 `max_lockout_secs`
+    pub fn lockout_secs(&self, count: u64) -> u64 {
+        let exponent = count.saturating_sub(1).min(63) as u32;
+        self.base_lockout_secs
+            .saturating_mul(2u64.saturating_pow(exponent))
+            .min(self.max_lockout_secs)
+    }
+}
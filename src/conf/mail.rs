@@ -0,0 +1,125 @@
+/// 邮件发送方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransport {
+    /// 只输出到日志，用于本地开发
+    Log,
+    /// 写入 `file_dir` 目录下的 .eml 文件，用于本地开发和测试
+    File,
+    /// 通过 SMTP 发送
+    Smtp,
+}
+
+/// SMTP 服务器配置
+///
+/// password 支持 `file:<path>` 的形式从文件读取
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct SmtpConfig {
+    host: String,
+    #[serde(default = "default_smtp_port")]
+    port: u16,
+    username: String,
+    password: String,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+impl SmtpConfig {
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+/// 邮件相关配置
+///
+/// - transport: 发送方式，log/file/smtp
+/// - from: 发件人
+/// - file_dir: file 方式下邮件的保存目录
+/// - smtp: smtp 方式下的服务器配置
+/// - password_reset_url: 重置密码页面的地址，`{token}` 会被替换为重置令牌
+/// - password_reset_ttl_secs: 重置令牌的有效期
+/// - password_reset_cooldown_secs: 同一个帐号两封重置邮件的最小间隔，冷却期内的请求不发邮件，也不作废已发出的令牌
+/// - password_reset_ip_limit: 同一个 IP 在统计窗口内允许申请重置密码的次数
+/// - password_reset_ip_window_secs: IP 申请次数的统计窗口
+/// - email_verification_url: 验证邮箱页面的地址，`{token}` 会被替换为验证令牌
+/// - email_verification_ttl_secs: 验证令牌的有效期
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct MailConfig {
+    transport: MailTransport,
+    from: String,
+    file_dir: String,
+    smtp: Option<SmtpConfig>,
+    password_reset_url: String,
+    password_reset_ttl_secs: u64,
+    password_reset_cooldown_secs: u64,
+    password_reset_ip_limit: u64,
+    password_reset_ip_window_secs: u64,
+    email_verification_url: String,
+    email_verification_ttl_secs: u64,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: MailTransport::Log,
+            from: "user_server <noreply@localhost>".into(),
+            file_dir: "./mails".into(),
+            smtp: None,
+            password_reset_url: "http://localhost:3000/reset-password?token={token}".into(),
+            password_reset_ttl_secs: 60 * 30,
+            password_reset_cooldown_secs: 60 * 5,
+            password_reset_ip_limit: 10,
+            password_reset_ip_window_secs: 60 * 60,
+            email_verification_url: "http://localhost:3000/verify-email?token={token}".into(),
+            email_verification_ttl_secs: 60 * 60 * 24,
+        }
+    }
+}
+
+impl MailConfig {
+    pub fn transport(&self) -> MailTransport {
+        self.transport
+    }
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+    pub fn file_dir(&self) -> &str {
+        &self.file_dir
+    }
+    pub fn smtp(&self) -> Option<&SmtpConfig> {
+        self.smtp.as_ref()
+    }
+    pub fn password_reset_url(&self) -> &str {
+        &self.password_reset_url
+    }
+    pub fn password_reset_ttl_secs(&self) -> u64 {
+        self.password_reset_ttl_secs
+    }
+    pub fn password_reset_cooldown_secs(&self) -> u64 {
+        self.password_reset_cooldown_secs
+    }
+    pub fn password_reset_ip_limit(&self) -> u64 {
+        self.password_reset_ip_limit
+    }
+    pub fn password_reset_ip_window_secs(&self) -> u64 {
+        self.password_reset_ip_window_secs
+    }
+    pub fn email_verification_url(&self) -> &str {
+        &self.email_verification_url
+    }
+    pub fn email_verification_ttl_secs(&self) -> u64 {
+        self.email_verification_ttl_secs
+    }
+}
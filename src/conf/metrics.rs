@@ -0,0 +1,40 @@
+/// Prometheus 指标相关配置
+///
+/// - enabled: 是否开启指标采集和 `/metrics` 接口
+/// - grpc_port: gRPC 服务单独暴露 `/metrics` 的端口
+/// - http_port: HTTP 服务单独暴露 `/metrics` 的端口，不与对外的业务端口共用
+/// - pool_sample_secs: 采集数据库连接池状态的间隔
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    enabled: bool,
+    grpc_port: u16,
+    http_port: u16,
+    pool_sample_secs: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            grpc_port: 9464,
+            http_port: 9465,
+            pool_sample_secs: 15,
+        }
+    }
+}
+
+impl MetricsConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+    pub fn grpc_port(&self) -> u16 {
+        self.grpc_port
+    }
+    pub fn http_port(&self) -> u16 {
+        self.http_port
+    }
+    pub fn pool_sample_secs(&self) -> u64 {
+        self.pool_sample_secs
+    }
+}
@@ -4,9 +4,19 @@ use crate::conf::app::AppConfig;
 
 pub mod app;
 pub mod database;
+pub mod enumeration;
 pub mod grpc;
 pub mod http;
+pub mod jwt;
+pub mod login_limit;
+pub mod mail;
+pub mod metrics;
+pub mod otel;
+pub mod password_hash;
+pub mod password_policy;
 pub mod redis;
+pub mod register;
+pub mod secret;
 
 // set the static config
 static APP_CONFIG: LazyLock<AppConfig> =
@@ -0,0 +1,63 @@
+/// 链路追踪数据的导出方式
+///
+/// - otlp: 通过 OTLP/gRPC 发送到 collector
+/// - file: 每个 span 一行 JSON 追加到文件，便于本地调试
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OtelExporter {
+    Otlp,
+    File,
+}
+
+/// OpenTelemetry 链路追踪配置
+///
+/// - enabled: 是否导出链路追踪数据，关闭时仍然传递 `traceparent`，日志中的 trace_id 照常可用
+/// - exporter: 导出方式
+/// - endpoint: OTLP collector 的地址
+/// - file_path: 使用文件导出时的文件路径
+/// - sample_ratio: 采样比例，0.0-1.0，上游已经决定采样的请求跟随上游
+/// - export_timeout_secs: 单次导出的超时时间
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct OtelConfig {
+    enabled: bool,
+    exporter: OtelExporter,
+    endpoint: String,
+    file_path: String,
+    sample_ratio: f64,
+    export_timeout_secs: u64,
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exporter: OtelExporter::Otlp,
+            endpoint: String::from("http://localhost:4317"),
+            file_path: String::from("logs/spans.jsonl"),
+            sample_ratio: 1.0,
+            export_timeout_secs: 10,
+        }
+    }
+}
+
+impl OtelConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+    pub fn exporter(&self) -> OtelExporter {
+        self.exporter
+    }
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+    pub fn file_path(&self) -> &str {
+        &self.file_path
+    }
+    pub fn sample_ratio(&self) -> f64 {
+        self.sample_ratio
+    }
+    pub fn export_timeout_secs(&self) -> u64 {
+        self.export_timeout_secs
+    }
+}
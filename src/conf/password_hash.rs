@@ -0,0 +1,79 @@
+/// 密码哈希相关配置，使用 Argon2id
+///
+/// - memory_kib: 内存开销，单位 KiB
+/// - iterations: 迭代次数
+/// - parallelism: 并行度
+/// - pepper: 当前使用的 pepper，作为 Argon2 的 secret 参与哈希，不保存在数据库中
+/// - previous_peppers: 轮换前的 pepper，只用于验证旧的哈希
+///
+/// 调整参数或轮换 pepper 后，已有的密码会在用户下次登录成功时重新哈希
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct PasswordHashConfig {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    pepper: Option<PepperConfig>,
+    previous_peppers: Vec<PepperConfig>,
+}
+
+/// pepper 配置
+///
+/// - id: 版本标识，1-8 个字节，写入哈希的 keyid 参数，验证时据此选择 pepper
+/// - secret: pepper 的内容，支持 `file:<path>` 的形式从文件读取
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct PepperConfig {
+    id: String,
+    secret: String,
+}
+
+impl PepperConfig {
+    pub fn new(id: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            secret: secret.into(),
+        }
+    }
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+}
+
+impl Default for PasswordHashConfig {
+    fn default() -> Self {
+        Self {
+            memory_kib: argon2::Params::DEFAULT_M_COST,
+            iterations: argon2::Params::DEFAULT_T_COST,
+            parallelism: argon2::Params::DEFAULT_P_COST,
+            pepper: None,
+            previous_peppers: Vec::new(),
+        }
+    }
+}
+
+impl PasswordHashConfig {
+    pub fn memory_kib(&self) -> u32 {
+        self.memory_kib
+    }
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+    pub fn parallelism(&self) -> u32 {
+        self.parallelism
+    }
+    pub fn pepper(&self) -> Option<&PepperConfig> {
+        self.pepper.as_ref()
+    }
+    pub fn previous_peppers(&self) -> &[PepperConfig] {
+        &self.previous_peppers
+    }
+
+    /// 转换成 Argon2 的参数，参数超出范围时返回错误
+    pub fn params(&self) -> anyhow::Result<argon2::Params> {
+        argon2::Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| anyhow::anyhow!("invalid password_hash config: {e}"))
+    }
+}
@@ -0,0 +1,72 @@
+/// 密码强度策略配置
+///
+/// - min_length / max_length: 密码长度范围，按字符计算
+/// - require_lowercase / require_uppercase / require_digit / require_symbol: 必须包含的字符类别
+/// - min_character_classes: 至少包含几类字符（小写字母、大写字母、数字、符号）
+/// - min_entropy_bits: 估算的最低熵，重复和连续的字符只计一半长度
+/// - reject_username: 是否拒绝包含用户名的密码
+/// - blocklist_file: 常见或已泄露密码列表，每行一个，不区分大小写，`#` 开头的行是注释
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct PasswordPolicyConfig {
+    min_length: usize,
+    max_length: usize,
+    require_lowercase: bool,
+    require_uppercase: bool,
+    require_digit: bool,
+    require_symbol: bool,
+    min_character_classes: usize,
+    min_entropy_bits: f64,
+    reject_username: bool,
+    blocklist_file: Option<String>,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            require_lowercase: false,
+            require_uppercase: false,
+            require_digit: false,
+            require_symbol: false,
+            min_character_classes: 1,
+            min_entropy_bits: 36.0,
+            reject_username: true,
+            blocklist_file: None,
+        }
+    }
+}
+
+impl PasswordPolicyConfig {
+    pub fn min_length(&self) -> usize {
+        self.min_length
+    }
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+    pub fn require_lowercase(&self) -> bool {
+        self.require_lowercase
+    }
+    pub fn require_uppercase(&self) -> bool {
+        self.require_uppercase
+    }
+    pub fn require_digit(&self) -> bool {
+        self.require_digit
+    }
+    pub fn require_symbol(&self) -> bool {
+        self.require_symbol
+    }
+    pub fn min_character_classes(&self) -> usize {
+        self.min_character_classes
+    }
+    pub fn min_entropy_bits(&self) -> f64 {
+        self.min_entropy_bits
+    }
+    pub fn reject_username(&self) -> bool {
+        self.reject_username
+    }
+    pub fn blocklist_file(&self) -> Option<&str> {
+        self.blocklist_file.as_deref()
+    }
+}
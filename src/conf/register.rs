@@ -0,0 +1,14 @@
+/// 注册相关配置
+///
+/// - email_required: 注册时是否必须填写邮箱
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct RegisterConfig {
+    email_required: bool,
+}
+
+impl RegisterConfig {
+    pub fn email_required(&self) -> bool {
+        self.email_required
+    }
+}
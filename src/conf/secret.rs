@@ -0,0 +1,24 @@
+/// 文件引用的前缀，如 `file:/run/secrets/jwt_secret`
+const FILE_PREFIX: &str = "file:";
+
+/// 解析配置中的密钥
+///
+/// # 功能描述
+/// 密钥可以直接写在配置中，也可以写成 `file:<path>` 的形式从文件读取（如 Docker/K8s secret），
+/// 两种形式都可以通过 `APP_` 前缀的环境变量覆盖。从文件读取时会去掉末尾的换行符。
+///
+/// # 参数
+/// - `value`: 配置中的原始值
+///
+/// # 返回值
+/// 返回密钥内容 `anyhow::Result<String>`
+pub fn resolve_secret(value: &str) -> anyhow::Result<String> {
+    match value.strip_prefix(FILE_PREFIX) {
+        Some(path) => {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Failed to read secret file {path}: {e}"))?;
+            Ok(content.trim_end_matches(['\r', '\n']).to_string())
+        }
+        None => Ok(value.to_string()),
+    }
+}
@@ -0,0 +1,124 @@
+use tonic::{Code, Status};
+
+/// 唯一约束冲突
+pub const UNIQUE_VIOLATION: &str = "23505";
+/// 外键约束冲突
+pub const FOREIGN_KEY_VIOLATION: &str = "23503";
+/// 非空约束冲突
+pub const NOT_NULL_VIOLATION: &str = "23502";
+/// 检查约束冲突
+pub const CHECK_VIOLATION: &str = "23514";
+/// 事务序列化失败
+pub const SERIALIZATION_FAILURE: &str = "40001";
+/// 检测到死锁
+pub const DEADLOCK_DETECTED: &str = "40P01";
+/// 语句超时或被取消
+pub const QUERY_CANCELED: &str = "57014";
+
+/// 把 Postgres 的 SQLSTATE 归类为 gRPC 状态码
+///
+/// # 功能描述
+/// - 唯一约束冲突：`AlreadyExists`
+/// - 外键约束冲突：`FailedPrecondition`
+/// - 非空、检查约束冲突以及数据格式错误（22 类）：`InvalidArgument`
+/// - 序列化失败、死锁：`Aborted`，客户端可以重试
+/// - 语句超时：`DeadlineExceeded`
+/// - 连接异常（08 类）、资源不足（53 类）、数据库关闭（57P 类）：`Unavailable`
+/// - 其他：`Internal`
+///
+/// # 参数
+/// - `sqlstate`: 五位的 SQLSTATE
+///
+/// # 返回值
+/// 返回对应的 gRPC 状态码
+pub fn classify_sqlstate(sqlstate: &str) -> Code {
+    match sqlstate {
+        UNIQUE_VIOLATION => Code::AlreadyExists,
+        FOREIGN_KEY_VIOLATION => Code::FailedPrecondition,
+        NOT_NULL_VIOLATION | CHECK_VIOLATION => Code::InvalidArgument,
+        SERIALIZATION_FAILURE | DEADLOCK_DETECTED => Code::Aborted,
+        QUERY_CANCELED => Code::DeadlineExceeded,
+        code if code.starts_with("22") => Code::InvalidArgument,
+        code if code.starts_with("08") || code.starts_with("53") || code.starts_with("57P") => {
+            Code::Unavailable
+        }
+        _ => Code::Internal,
+    }
+}
+
+/// 把 sqlx 的错误归类为 gRPC 状态码
+///
+/// # 功能描述
+/// 数据库返回的错误按 SQLSTATE 归类；连接池超时、连接池关闭、网络和 TLS 错误视为 `Unavailable`；
+/// 查询不到记录视为 `NotFound`；其他错误视为 `Internal`。
+pub fn classify(e: &sqlx::Error) -> Code {
+    match e {
+        sqlx::Error::Database(db_error) => db_error
+            .code()
+            .map(|code| classify_sqlstate(&code))
+            .unwrap_or(Code::Internal),
+        sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_) => Code::Unavailable,
+        sqlx::Error::RowNotFound => Code::NotFound,
+        _ => Code::Internal,
+    }
+}
+
+/// 把数据库错误转换为可以返回给调用方的 `tonic::Status`
+///
+/// # 功能描述
+/// 按 `classify` 归类后返回固定的提示信息，不包含 SQL、约束名等内部细节。
+/// 服务端错误记录 error 日志，调用方导致的错误（如唯一约束冲突）记录 warn 日志。
+///
+/// # 参数
+/// - `context`: 日志中说明正在执行的操作，如 `查询用户失败`
+/// - `e`: 数据库错误
+///
+/// # 示例
+/// ```
+/// use tonic::Code;
+/// use user_server::db::error::db_status;
+///
+/// let status = db_status("更新用户失败", sqlx::Error::PoolTimedOut);
+/// assert_eq!(status.code(), Code::Unavailable);
+/// assert_eq!(status.message(), "数据库暂时不可用，请稍后重试！");
+/// ```
+pub fn db_status(context: &str, e: sqlx::Error) -> Status {
+    let code = classify(&e);
+    let message = match code {
+        Code::AlreadyExists => "记录已存在！",
+        Code::FailedPrecondition => "关联的数据不存在！",
+        Code::InvalidArgument => "数据不符合要求！",
+        Code::Aborted => "数据正在被修改，请稍后重试！",
+        Code::DeadlineExceeded => "数据库处理超时，请稍后重试！",
+        Code::Unavailable => "数据库暂时不可用，请稍后重试！",
+        Code::NotFound => "记录不存在！",
+        _ => "服务器内部错误",
+    };
+    match code {
+        Code::AlreadyExists | Code::FailedPrecondition | Code::InvalidArgument | Code::NotFound => {
+            tracing::warn!("{}: {:?}", context, e)
+        }
+        _ => tracing::error!("{}: {:?}", context, e),
+    }
+    Status::new(code, message)
+}
+
+/// 返回把数据库错误转换为 `tonic::Status` 的闭包，便于在 `map_err` 中使用
+///
+/// # 参数
+/// - `context`: 日志中说明正在执行的操作
+///
+/// # 示例
+/// ```
+/// use tonic::{Code, Status};
+/// use user_server::db::error::db_error;
+///
+/// let result: Result<(), Status> = Err(sqlx::Error::RowNotFound).map_err(db_error("查询用户失败"));
+/// assert_eq!(result.unwrap_err().code(), Code::NotFound);
+/// ```
+pub fn db_error(context: &str) -> impl FnOnce(sqlx::Error) -> Status + '_ {
+    move |e| db_status(context, e)
+}
@@ -2,10 +2,17 @@ use std::sync::OnceLock;
 
 use sqlx::PgPool;
 
+use crate::db::redis::RedisPool;
+
+pub mod error;
 pub mod pgsql;
+pub mod redis;
+pub mod trace;
 
 // 全局 Postgres 数据库连接池实例
 static GLOBAL_DATABASE_POOL: OnceLock<PgPool> = OnceLock::new();
+// 全局 Redis 连接池实例
+static GLOBAL_REDIS_POOL: OnceLock<RedisPool> = OnceLock::new();
 /// 获取全局的静态 Postgres 数据库连接池引用
 pub fn get_global_database_pool() -> &'static PgPool {
     GLOBAL_DATABASE_POOL.get().expect("database pool lost")
@@ -16,3 +23,13 @@ pub async fn set_global_db(db: PgPool) -> anyhow::Result<()> {
         .set(db)
         .map_err(|_| anyhow::anyhow!("failed to set global database pool"))
 }
+/// 获取全局的静态 Redis 连接池引用
+pub fn get_global_redis_pool() -> &'static RedisPool {
+    GLOBAL_REDIS_POOL.get().expect("redis pool lost")
+}
+/// 初始化全局的静态 Redis 连接池
+pub async fn set_global_redis(redis: RedisPool) -> anyhow::Result<()> {
+    GLOBAL_REDIS_POOL
+        .set(redis)
+        .map_err(|_| anyhow::anyhow!("failed to set global redis pool"))
+}
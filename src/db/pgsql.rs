@@ -1,8 +1,9 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use sqlx::{PgPool, postgres::PgPoolOptions};
+use sqlx::{PgPool, Postgres, Transaction, pool::PoolConnection, postgres::PgPoolOptions};
+use tonic::Status;
 
-use crate::conf::database::DbConfig;
+use crate::{conf::database::DbConfig, db::error::db_error, metrics::DB_POOL_ACQUIRE_WAIT_SECONDS};
 
 /// 查询数据库连接状态
 #[derive(sqlx::FromRow)]
@@ -77,3 +78,38 @@ async fn log_detailed_database_info(pool: &PgPool) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// 检查 Postgres 是否可用，执行 `SELECT 1`，超时视为不可用
+pub async fn ping(pool: &PgPool, timeout: Duration) -> anyhow::Result<()> {
+    tokio::time::timeout(timeout, sqlx::query("SELECT 1").execute(pool))
+        .await
+        .map_err(|_| anyhow::anyhow!("Postgres 健康检查超时"))??;
+    Ok(())
+}
+
+/// 从连接池获取连接
+///
+/// # 功能描述
+/// 业务代码统一通过这里获取连接，等待时间（包括超时失败的等待）记入 `db_pool_acquire_wait_seconds`，
+/// 连接池繁忙时等待时间会明显变长。
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+pub async fn acquire(pool: &PgPool) -> Result<PoolConnection<Postgres>, Status> {
+    let start = Instant::now();
+    let connection = pool.acquire().await;
+    metrics::histogram!(DB_POOL_ACQUIRE_WAIT_SECONDS).record(start.elapsed().as_secs_f64());
+    connection.map_err(db_error("获取数据库连接失败"))
+}
+
+/// 从连接池获取连接并开启事务，获取连接的等待时间同样记入直方图
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+pub async fn begin(pool: &PgPool) -> Result<Transaction<'static, Postgres>, Status> {
+    let connection = acquire(pool).await?;
+    // 与 `Pool::begin` 相同，事务持有连接，提交或回滚后连接归还连接池
+    Transaction::begin(connection, None)
+        .await
+        .map_err(db_error("开启事务失败"))
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use mobc_redis::{RedisConnectionManager, redis};
+
+use crate::conf::redis::RedisConfig;
+
+/// Redis 连接池类型
+pub type RedisPool = mobc::Pool<RedisConnectionManager>;
+
+/// 使用配置初始化 Redis 连接池
+pub async fn init_redis_pool_with_config(config: &RedisConfig) -> anyhow::Result<RedisPool> {
+    let client = redis::Client::open(config.url())
+        .map_err(|e| anyhow::anyhow!("无法解析 Redis 连接地址：{}", e))?;
+    let pool = mobc::Pool::builder()
+        .max_open(config.max_open())
+        .max_idle(config.max_idle())
+        .get_timeout(Some(Duration::from_secs(config.timeout_sec())))
+        .build(RedisConnectionManager::new(client));
+
+    // 测试连接
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|e| anyhow::anyhow!("无法连接到 Redis：{}", e))?;
+    let _: String = redis::cmd("PING")
+        .query_async(&mut *conn)
+        .await
+        .map_err(|e| anyhow::anyhow!("测试连接 Redis 失败：{}", e))?;
+
+    tracing::info!(
+        "✅ Redis pool initialized with {} max open, {} max idle connections",
+        config.max_open(),
+        config.max_idle()
+    );
+
+    Ok(pool)
+}
+
+/// 检查 Redis 是否可用，执行 `PING`，超时视为不可用
+pub async fn ping(pool: &RedisPool, timeout: Duration) -> anyhow::Result<()> {
+    tokio::time::timeout(timeout, async {
+        let mut conn = pool.get().await?;
+        let _: String = redis::cmd("PING").query_async(&mut *conn).await?;
+        anyhow::Ok(())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Redis 健康检查超时"))?
+}
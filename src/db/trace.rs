@@ -0,0 +1,34 @@
+use tracing::Span;
+
+/// 创建一次数据库查询的 span
+///
+/// # 功能描述
+/// span 名称为 `SELECT user` 这样的操作加表名，不记录 SQL 参数。
+///
+/// # 参数
+/// - `operation`: SQL 操作，如 `SELECT`、`UPDATE`
+/// - `table`: 表名
+///
+/// # 示例
+/// ```
+/// use sqlx::PgPool;
+/// use tracing::Instrument;
+/// use user_server::db::trace::db_span;
+///
+/// async fn count_users(pool: &PgPool) -> Result<i64, sqlx::Error> {
+///     sqlx::query_scalar(r#"SELECT COUNT(*) FROM "user""#)
+///         .fetch_one(pool)
+///         .instrument(db_span("SELECT", "user"))
+///         .await
+/// }
+/// ```
+pub fn db_span(operation: &'static str, table: &'static str) -> Span {
+    tracing::info_span!(
+        "db.query",
+        otel.name = %format!("{operation} {table}"),
+        otel.kind = "client",
+        db.system = "postgresql",
+        db.operation = operation,
+        db.collection.name = table,
+    )
+}
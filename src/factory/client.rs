@@ -1,6 +1,18 @@
 use std::{sync::Arc, time::Duration};
 
-use crate::{pb::user::user_service_client::UserServiceClient, response::ApiResult};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+use tonic_health::pb::health_client::HealthClient;
+
+use crate::{
+    middlewares::auth::auth_layer::AccessToken,
+    pb::user::user_service_client::UserServiceClient,
+    response::{ApiResult, errors::ApiError},
+    utils::trace::TraceContextInterceptor,
+};
+
+/// 携带链路上下文的 gRPC 连接，每次调用都会把当前请求的 `traceparent` 传给 gRPC 服务
+pub type TracedChannel = InterceptedService<Channel, TraceContextInterceptor>;
+
 /// 定义一个 GRPC 客户端工厂
 #[derive(Debug, Clone)]
 pub struct GrpcUserClientFactory {
@@ -20,8 +32,26 @@ impl GrpcUserClientFactory {
         Ok(Self { channel })
     }
 
+    /// 创建 gRPC 健康检查客户端，与业务客户端共用连接
+    pub fn create_health_client(&self) -> HealthClient<TracedChannel> {
+        HealthClient::with_interceptor(self.channel.as_ref().clone(), TraceContextInterceptor)
+    }
+
     /// 创建一个新的 GRPC 客户端。
-    pub async fn create_client(&self) -> ApiResult<UserServiceClient<tonic::transport::Channel>> {
-        Ok(UserServiceClient::new(self.channel.as_ref().clone()))
+    pub async fn create_client(&self) -> ApiResult<UserServiceClient<TracedChannel>> {
+        Ok(UserServiceClient::with_interceptor(
+            self.channel.as_ref().clone(),
+            TraceContextInterceptor,
+        ))
     }
 }
+
+/// 创建携带 access token 的 gRPC 请求，用于调用需要认证的方法
+pub fn authorized_request<T>(message: T, token: &AccessToken) -> ApiResult<tonic::Request<T>> {
+    let mut request = tonic::Request::new(message);
+    let value = format!("Bearer {}", token.0)
+        .parse()
+        .map_err(|_| ApiError::Unauthenticated(String::from("无效的 access token")))?;
+    request.metadata_mut().insert("authorization", value);
+    Ok(request)
+}
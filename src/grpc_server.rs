@@ -1,10 +1,33 @@
+use std::time::Duration;
+
 use tonic::transport::Server;
+use tonic_health::pb::health_server::SERVICE_NAME as HEALTH_SERVICE_NAME;
+use tower_http::trace::TraceLayer;
 use user_server::{
+    app::server::shutdown_signal,
+    common::client_info::init_trusted_proxies,
     conf::app::AppConfig,
-    db::{get_global_database_pool, pgsql::init_database_pool_with_config, set_global_db},
-    log::logger::init_logger_with_file,
-    pb::user::user_service_server::UserServiceServer,
-    service_impl::user::UserServiceImpl,
+    db::{
+        get_global_database_pool, pgsql::init_database_pool_with_config,
+        redis::init_redis_pool_with_config, set_global_db, set_global_redis,
+    },
+    log::{logger::init_logger_with_file, otel::init_tracer_provider},
+    mail::build_mailer,
+    metrics::{init_metrics, pool::sample_pool_metrics},
+    middlewares::{
+        auth::{
+            grpc_auth::GrpcAuthLayer,
+            jwt::{get_global_jwt, init_global_jwt},
+        },
+        metrics::grpc_metrics::GrpcMetricsLayer,
+    },
+    pb::user::user_service_server::{SERVICE_NAME, UserServiceServer},
+    router::metrics::serve_metrics,
+    service_impl::{
+        health::report_database_health, login_limit::LoginLimiter, password_policy::PasswordPolicy,
+        user::UserServiceImpl,
+    },
+    utils::{crypto::init_password_hasher, latency::GrpcLatencyOnResponse, trace::make_grpc_span},
 };
 
 #[tokio::main]
@@ -13,23 +36,85 @@ async fn main() -> anyhow::Result<()> {
     // 1. 读取配置信息
     let config = AppConfig::load()?;
     let log_level = config.grpc_config().log_level();
-    // 2. 初始化日志
-    let _guard = init_logger_with_file(log_level).await?;
+    // 2. 初始化日志和链路追踪
+    let tracer_provider = init_tracer_provider(config.otel(), "user-server-grpc")?;
+    let _guard = init_logger_with_file(log_level, &tracer_provider).await?;
+    // 初始化 JWT，用于签发和校验 token
+    init_global_jwt(config.jwt(), config.is_dev())?;
+    // 初始化密码哈希参数
+    init_password_hasher(config.password_hash())?;
+    // 初始化可信代理，只有来自 HTTP 网关的调用才能转发客户端 IP
+    init_trusted_proxies(config.grpc_config().trusted_proxies())?;
     // 3. 初始化数据库连接池
     let db = init_database_pool_with_config(config.database()).await?;
     set_global_db(db).await?;
-    // 4. 创建服务
-    let srv = UserServiceImpl::new(get_global_database_pool());
-    // 5. 服务地址
+    // 4. 初始化 Redis 连接池
+    let redis = init_redis_pool_with_config(config.redis()).await?;
+    set_global_redis(redis).await?;
+    // 5. 创建服务
+    let srv = UserServiceImpl::new(
+        get_global_database_pool(),
+        LoginLimiter::new(config.login_limit().clone()),
+        build_mailer(config.mail())?,
+        config.mail().clone(),
+        config.register().clone(),
+        PasswordPolicy::new(config.password_policy().clone())?,
+        config.enumeration().clone(),
+    );
+    // 6. 服务地址
     let mut addr = format!("0.0.0.0:{}", config.grpc_config().port()).parse()?;
     if config.is_dev() {
         addr = format!("[::1]:{}", config.grpc_config().port()).parse()?;
     }
     tracing::info!("Starting UserService on {}", addr);
-    // 6. 启动服务
+    // 7. 认证层，登录、注册等方法和健康检查不需要认证，UserExists 会暴露用户名是否存在，只对已认证的调用方开放
+    let auth_layer = GrpcAuthLayer::new(get_global_jwt())
+        .with_public_methods(
+            SERVICE_NAME,
+            [
+                "UserLogin",
+                "UserRegister",
+                "RefreshToken",
+                "RequestPasswordReset",
+                "ConfirmPasswordReset",
+                "VerifyEmail",
+            ],
+        )
+        .with_public_methods(HEALTH_SERVICE_NAME, ["Check", "Watch"]);
+    // 8. 健康检查服务，状态随数据库连接状态变化
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    tokio::spawn(report_database_health(
+        health_reporter,
+        get_global_database_pool(),
+    ));
+    // 9. 指标，单独监听一个端口暴露 /metrics
+    if config.metrics().enabled() {
+        init_metrics()?;
+        tokio::spawn(sample_pool_metrics(
+            get_global_database_pool(),
+            Duration::from_secs(config.metrics().pool_sample_secs()),
+        ));
+        serve_metrics(config.metrics().grpc_port()).await?;
+    }
+    // 10. 每次调用一个 span，记录方法、调用方地址和请求 id，完成时输出用时和状态码，
+    //     metadata 带有 traceparent 时继续 HTTP 服务的链路
+    let trace_layer = TraceLayer::new_for_grpc()
+        .make_span_with(make_grpc_span)
+        .on_request(())
+        .on_response(GrpcLatencyOnResponse)
+        .on_failure(());
+    // 11. 启动服务，追踪层和指标层在认证层之外，认证失败的调用也会计入
     Server::builder()
+        .layer(trace_layer)
+        .layer(GrpcMetricsLayer)
+        .layer(auth_layer)
+        .add_service(health_service)
         .add_service(UserServiceServer::new(srv))
-        .serve(addr)
+        .serve_with_shutdown(addr, shutdown_signal())
         .await?;
+    // 12. 发送剩余的链路追踪数据
+    if let Err(e) = tracer_provider.shutdown() {
+        tracing::warn!("关闭链路追踪失败: {:?}", e);
+    }
     Ok(())
 }
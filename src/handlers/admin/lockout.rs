@@ -0,0 +1,41 @@
+use axum::{Extension, debug_handler, extract::State};
+
+use crate::{
+    common::{path::Path, valid::ValidQuery},
+    factory::client::authorized_request,
+    handlers::common::model::ClearLockoutQuery,
+    middlewares::auth::{auth_layer::AccessToken, principal::Principal},
+    pb::user::ClearLoginLockoutRequest,
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn clear_login_lockout_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Extension(token): Extension<AccessToken>,
+    Path(username): Path<String>,
+    ValidQuery(query): ValidQuery<ClearLockoutQuery>,
+) -> ApiResult<ApiResponse<()>> {
+    tracing::info!(
+        "admin {} is clearing login lockout of {}",
+        principal.username,
+        username
+    );
+    let clear_request = authorized_request(
+        ClearLoginLockoutRequest {
+            username,
+            ip: query.ip,
+        },
+        &token,
+    )?;
+    // 解除用户名（以及可选的 IP）的登录锁定
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .clear_login_lockout(clear_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success_with_msg(grpc_response.result))
+}
@@ -0,0 +1,35 @@
+use axum::{Extension, debug_handler, extract::State};
+
+use crate::{
+    common::{path::Path, valid::ValidQuery},
+    factory::client::authorized_request,
+    handlers::common::model::LoginHistoryQuery,
+    middlewares::auth::auth_layer::AccessToken,
+    pb::user::{ListLoginHistoryRequest, ListLoginHistoryResponse},
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn list_user_logins_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(token): Extension<AccessToken>,
+    Path(user_id): Path<i32>,
+    ValidQuery(query): ValidQuery<LoginHistoryQuery>,
+) -> ApiResult<ApiResponse<ListLoginHistoryResponse>> {
+    let history_request = authorized_request(
+        ListLoginHistoryRequest {
+            user_id,
+            limit: query.limit.unwrap_or_default(),
+        },
+        &token,
+    )?;
+    // 查询指定用户最近的登录记录
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .list_login_history(history_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success(grpc_response))
+}
@@ -0,0 +1,3 @@
+pub mod lockout;
+pub mod login_history;
+pub mod users;
@@ -0,0 +1,99 @@
+use axum::{Extension, debug_handler, extract::State};
+
+use crate::{
+    common::{
+        path::Path,
+        valid::{ValidJson, ValidQuery},
+    },
+    factory::client::authorized_request,
+    handlers::common::model::{ListUsersQuery, SetUserLevelParam, SetUserStatusParam},
+    middlewares::auth::auth_layer::AccessToken,
+    pb::user::{
+        GetUserRequest, ListUsersRequest, ListUsersResponse, SetUserLevelRequest,
+        SetUserStatusRequest, UserInfo,
+    },
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn list_users_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(token): Extension<AccessToken>,
+    ValidQuery(query): ValidQuery<ListUsersQuery>,
+) -> ApiResult<ApiResponse<ListUsersResponse>> {
+    let list_request = authorized_request(ListUsersRequest::from(query), &token)?;
+    // 分页查询用户
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .list_users(list_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success(grpc_response))
+}
+
+#[debug_handler]
+pub async fn get_user_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(token): Extension<AccessToken>,
+    Path(user_id): Path<i32>,
+) -> ApiResult<ApiResponse<UserInfo>> {
+    let get_request = authorized_request(GetUserRequest { user_id }, &token)?;
+    // 查询指定用户的资料
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .get_user(get_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success(grpc_response))
+}
+
+#[debug_handler]
+pub async fn set_user_status_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(token): Extension<AccessToken>,
+    Path(user_id): Path<i32>,
+    ValidJson(params): ValidJson<SetUserStatusParam>,
+) -> ApiResult<ApiResponse<UserInfo>> {
+    let status_request = authorized_request(
+        SetUserStatusRequest {
+            user_id,
+            is_open: params.is_open,
+        },
+        &token,
+    )?;
+    // 启用或禁用帐号，禁用后该用户的全部会话立即失效
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .set_user_status(status_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success(grpc_response))
+}
+
+#[debug_handler]
+pub async fn set_user_level_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(token): Extension<AccessToken>,
+    Path(user_id): Path<i32>,
+    ValidJson(params): ValidJson<SetUserLevelParam>,
+) -> ApiResult<ApiResponse<UserInfo>> {
+    let level_request = authorized_request(
+        SetUserLevelRequest {
+            user_id,
+            level: params.level,
+        },
+        &token,
+    )?;
+    // 修改用户等级
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .set_user_level(level_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success(grpc_response))
+}
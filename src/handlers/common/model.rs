@@ -1,32 +1,168 @@
-use crate::pb::user::{UserLoginRequest, UserRegisterRequest};
+use crate::pb::user::{
+    ChangePasswordRequest, ConfirmPasswordResetRequest, ListUsersRequest, RefreshTokenRequest,
+    RequestPasswordResetRequest, UserLoginRequest, UserRegisterRequest, VerifyEmailRequest,
+};
 
 /// 定义注册用户参数
 #[derive(Debug, serde::Deserialize, serde::Serialize, Clone, validator::Validate)]
 pub struct RegisterUserParam {
     #[validate(length(min = 2, max = 20, message = "用户名长度必须在 2-20 之间"))]
     pub username: String,
-    #[validate(length(min = 6, max = 20, message = "密码长度必须在 6-20 之间"))]
+    #[validate(length(min = 1, max = 1024, message = "密码不能为空且不能超过 1024 个字符"))]
     pub password: String,
+    /// 邮箱，是否必填由服务端的 register.email_required 决定
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(
+        email(message = "邮箱格式不正确"),
+        length(max = 100, message = "邮箱长度不能超过 100")
+    )]
+    pub email: Option<String>,
 }
 
-/// 定义登录用户参数
+/// 定义登录用户参数，username 可以是用户名或已验证的邮箱
 #[derive(Debug, serde::Deserialize, Clone, validator::Validate)]
 pub struct LoginUserParam {
-    #[validate(length(min = 2, max = 20, message = "用户名长度必须在 2-20 之间"))]
+    #[validate(length(min = 2, max = 100, message = "用户名或邮箱长度必须在 2-100 之间"))]
     pub username: String,
-    #[validate(length(min = 6, max = 20, message = "密码长度必须在 6-20 之间"))]
+    #[validate(length(min = 1, max = 1024, message = "密码不能为空且不能超过 1024 个字符"))]
     pub password: String,
 }
 
+/// 定义修改密码参数，新密码的强度由服务端的密码策略检查
+#[derive(Debug, serde::Deserialize, Clone, validator::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordParam {
+    #[validate(length(min = 1, message = "当前密码不能为空"))]
+    pub current_password: String,
+    #[validate(length(min = 1, max = 1024, message = "密码不能为空且不能超过 1024 个字符"))]
+    pub new_password: String,
+}
+
+/// 定义申请重置密码参数
+#[derive(Debug, serde::Deserialize, Clone, validator::Validate)]
+pub struct RequestPasswordResetParam {
+    #[validate(length(min = 2, max = 20, message = "用户名长度必须在 2-20 之间"))]
+    pub username: String,
+}
+
+/// 定义确认重置密码参数，新密码的强度由服务端的密码策略检查
+#[derive(Debug, serde::Deserialize, Clone, validator::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmPasswordResetParam {
+    #[validate(length(min = 1, message = "token 不能为空"))]
+    pub token: String,
+    #[validate(length(min = 1, max = 1024, message = "密码不能为空且不能超过 1024 个字符"))]
+    pub new_password: String,
+}
+
+/// 定义验证邮箱参数
+#[derive(Debug, serde::Deserialize, Clone, validator::Validate)]
+pub struct VerifyEmailParam {
+    #[validate(length(min = 1, message = "token 不能为空"))]
+    pub token: String,
+}
+
+/// 定义刷新令牌参数
+#[derive(Debug, serde::Deserialize, Clone, validator::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenParam {
+    #[validate(length(min = 1, message = "refreshToken 不能为空"))]
+    pub refresh_token: String,
+}
+
+/// 定义退出登录参数
+#[derive(Debug, serde::Deserialize, Clone, Default, validator::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutParam {
+    /// 可选的 refresh token，传入时一并吊销其所在的令牌族
+    #[serde(default)]
+    pub refresh_token: String,
+}
+
+/// 定义解除登录锁定的查询参数
+#[derive(Debug, serde::Deserialize, Clone, Default, validator::Validate)]
+pub struct ClearLockoutQuery {
+    /// 可选的客户端 IP，传入时一并解除该 IP 的锁定
+    #[validate(ip(message = "ip 格式不正确"))]
+    pub ip: Option<String>,
+}
+
+/// 定义查询登录历史的参数
+#[derive(Debug, serde::Deserialize, Clone, Default, validator::Validate)]
+pub struct LoginHistoryQuery {
+    /// 返回的记录数，默认 20 条
+    #[validate(range(min = 1, max = 100, message = "limit 必须在 1-100 之间"))]
+    pub limit: Option<i32>,
+}
+
+/// 定义管理员查询用户列表的参数
+#[derive(Debug, serde::Deserialize, Clone, Default, validator::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ListUsersQuery {
+    /// 页码，从 1 开始
+    #[validate(range(min = 1, message = "page 必须大于 0"))]
+    pub page: Option<i32>,
+    /// 每页的记录数，默认 20 条
+    #[validate(range(min = 1, max = 100, message = "pageSize 必须在 1-100 之间"))]
+    pub page_size: Option<i32>,
+    /// 用户等级：guest, member, vip, admin
+    pub level: Option<String>,
+    /// 是否启用
+    pub is_open: Option<bool>,
+    /// 创建时间的起点（包含），RFC 3339 格式
+    pub created_after: Option<String>,
+    /// 创建时间的终点（不包含），RFC 3339 格式
+    pub created_before: Option<String>,
+    /// 用户名前缀
+    #[validate(length(max = 20, message = "用户名前缀不能超过 20 个字符"))]
+    pub username_prefix: Option<String>,
+}
+
+/// 定义启用或禁用用户的参数
+#[derive(Debug, serde::Deserialize, Clone, validator::Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SetUserStatusParam {
+    pub is_open: bool,
+}
+
+/// 定义修改用户等级的参数
+#[derive(Debug, serde::Deserialize, Clone, validator::Validate)]
+pub struct SetUserLevelParam {
+    /// 用户等级：guest, member, vip, admin
+    #[validate(length(min = 1, message = "level 不能为空"))]
+    pub level: String,
+}
+
+impl From<ListUsersQuery> for ListUsersRequest {
+    fn from(value: ListUsersQuery) -> Self {
+        ListUsersRequest {
+            page: value.page.unwrap_or_default(),
+            page_size: value.page_size.unwrap_or_default(),
+            level: value.level,
+            is_open: value.is_open,
+            created_after: value.created_after,
+            created_before: value.created_before,
+            username_prefix: value.username_prefix,
+        }
+    }
+}
+
 impl From<RegisterUserParam> for UserRegisterRequest {
     fn from(value: RegisterUserParam) -> Self {
         UserRegisterRequest {
             username: value.username,
             password: value.password,
+            email: value.email,
         }
     }
 }
 
+impl From<VerifyEmailParam> for VerifyEmailRequest {
+    fn from(value: VerifyEmailParam) -> Self {
+        VerifyEmailRequest { token: value.token }
+    }
+}
+
 impl From<LoginUserParam> for UserLoginRequest {
     fn from(value: LoginUserParam) -> Self {
         UserLoginRequest {
@@ -36,6 +172,40 @@ impl From<LoginUserParam> for UserLoginRequest {
     }
 }
 
+impl From<ChangePasswordParam> for ChangePasswordRequest {
+    fn from(value: ChangePasswordParam) -> Self {
+        ChangePasswordRequest {
+            current_password: value.current_password,
+            new_password: value.new_password,
+        }
+    }
+}
+
+impl From<RequestPasswordResetParam> for RequestPasswordResetRequest {
+    fn from(value: RequestPasswordResetParam) -> Self {
+        RequestPasswordResetRequest {
+            username: value.username,
+        }
+    }
+}
+
+impl From<ConfirmPasswordResetParam> for ConfirmPasswordResetRequest {
+    fn from(value: ConfirmPasswordResetParam) -> Self {
+        ConfirmPasswordResetRequest {
+            token: value.token,
+            new_password: value.new_password,
+        }
+    }
+}
+
+impl From<RefreshTokenParam> for RefreshTokenRequest {
+    fn from(value: RefreshTokenParam) -> Self {
+        RefreshTokenRequest {
+            refresh_token: value.refresh_token,
+        }
+    }
+}
+
 #[derive(Debug, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginResult {
@@ -0,0 +1,25 @@
+use axum::{Extension, debug_handler, extract::State};
+
+use crate::{
+    factory::client::authorized_request,
+    middlewares::auth::auth_layer::AccessToken,
+    pb::user::SendEmailVerificationRequest,
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn send_email_verification_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(token): Extension<AccessToken>,
+) -> ApiResult<ApiResponse<()>> {
+    let send_request = authorized_request(SendEmailVerificationRequest {}, &token)?;
+    // 重新发送当前用户的验证邮件
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .send_email_verification(send_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success_with_msg(grpc_response.result))
+}
@@ -0,0 +1,4 @@
+pub mod email;
+pub mod logins;
+pub mod password;
+pub mod profile;
@@ -0,0 +1,31 @@
+use axum::{Extension, debug_handler, extract::State};
+
+use crate::{
+    common::{client_info::ClientInfo, valid::ValidJson},
+    factory::client::authorized_request,
+    handlers::common::model::ChangePasswordParam,
+    middlewares::auth::auth_layer::AccessToken,
+    pb::user::{ChangePasswordRequest, UserLoginResponse},
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn change_password_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(token): Extension<AccessToken>,
+    client_info: ClientInfo,
+    ValidJson(params): ValidJson<ChangePasswordParam>,
+) -> ApiResult<ApiResponse<UserLoginResponse>> {
+    let mut change_request = authorized_request(ChangePasswordRequest::from(params), &token)?;
+    client_info.forward(&mut change_request);
+    // 修改密码，其他会话全部失效，返回当前会话的新 token
+    let mut client = grpc_factory.create_client().await?;
+    // 错误码和 HTTP 状态码由 gRPC 错误决定
+    let grpc_response = client
+        .change_password(change_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success(grpc_response))
+}
@@ -0,0 +1,31 @@
+use axum::{Extension, debug_handler, extract::State};
+
+use crate::{
+    factory::client::authorized_request,
+    middlewares::auth::{auth_layer::AccessToken, principal::Principal},
+    pb::user::{GetUserRequest, UserInfo},
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn get_profile_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Extension(token): Extension<AccessToken>,
+) -> ApiResult<ApiResponse<UserInfo>> {
+    let get_user_request = authorized_request(
+        GetUserRequest {
+            user_id: principal.id,
+        },
+        &token,
+    )?;
+    // 查询当前登录用户的资料
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .get_user(get_user_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success(grpc_response))
+}
@@ -1,2 +1,4 @@
+pub mod admin;
 pub mod common;
+pub mod me;
 pub mod user;
@@ -0,0 +1,26 @@
+use axum::{debug_handler, extract::State};
+
+use crate::{
+    common::valid::ValidJson,
+    handlers::common::model::VerifyEmailParam,
+    pb::user::VerifyEmailRequest,
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn verify_email_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    ValidJson(params): ValidJson<VerifyEmailParam>,
+) -> ApiResult<ApiResponse<()>> {
+    let verify_request: VerifyEmailRequest = params.into();
+    // 使用邮件中的令牌验证邮箱
+    let mut client = grpc_factory.create_client().await?;
+    // 错误码和 HTTP 状态码由 gRPC 错误决定
+    let grpc_response = client
+        .verify_email(verify_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success_with_msg(grpc_response.result))
+}
@@ -1,7 +1,7 @@
 use axum::{debug_handler, extract::State};
 
 use crate::{
-    common::valid::ValidJson,
+    common::{client_info::ClientInfo, valid::ValidJson},
     handlers::common::model::LoginUserParam,
     pb::user::{UserLoginRequest, UserLoginResponse},
     response::{ApiResult, errors::ApiError, resp::ApiResponse},
@@ -11,19 +11,19 @@ use crate::{
 #[debug_handler]
 pub async fn user_login_handler(
     State(AppState { grpc_factory, .. }): State<AppState>,
-    // Extension(_principal): Extension<Principal>,
-    // ConnectInfo(_addr): ConnectInfo<SocketAddr>,
+    client_info: ClientInfo,
     ValidJson(params): ValidJson<LoginUserParam>,
 ) -> ApiResult<ApiResponse<UserLoginResponse>> {
-    let user_login_request: UserLoginRequest = params.into();
-    // 查询用户名是否已经存在
+    let mut user_login_request = tonic::Request::new(UserLoginRequest::from(params));
+    // 转发客户端 IP 和 User-Agent，用于登录限流和登录历史
+    client_info.forward(&mut user_login_request);
+    // 调用 gRPC 服务登录
     let mut client = grpc_factory.create_client().await?;
-    let grpc_response = match client.user_login(user_login_request).await {
-        Ok(response) => response.into_inner(),
-        Err(status) => {
-            tracing::error!("grpc error: {:?}", status);
-            return Err(ApiError::GrpcError(status));
-        }
-    };
+    // 错误码和 HTTP 状态码由 gRPC 错误决定
+    let grpc_response = client
+        .user_login(user_login_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
     Ok(ApiResponse::success(grpc_response))
 }
@@ -0,0 +1,56 @@
+use axum::{Extension, debug_handler, extract::State};
+
+use crate::{
+    common::valid::ValidJson,
+    factory::client::authorized_request,
+    handlers::common::model::LogoutParam,
+    middlewares::auth::{auth_layer::AccessToken, principal::Principal},
+    pb::user::{RevokeUserSessionsRequest, UserLogoutRequest},
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn user_logout_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(token): Extension<AccessToken>,
+    ValidJson(params): ValidJson<LogoutParam>,
+) -> ApiResult<ApiResponse<()>> {
+    let logout_request = authorized_request(
+        UserLogoutRequest {
+            access_token: token.0.clone(),
+            refresh_token: params.refresh_token,
+        },
+        &token,
+    )?;
+    // 吊销当前的 access_token 和 refresh_token
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .user_logout(logout_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success_with_msg(grpc_response.result))
+}
+
+#[debug_handler]
+pub async fn revoke_user_sessions_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Extension(token): Extension<AccessToken>,
+) -> ApiResult<ApiResponse<()>> {
+    let revoke_request = authorized_request(
+        RevokeUserSessionsRequest {
+            user_id: principal.id,
+        },
+        &token,
+    )?;
+    // 吊销当前用户的全部会话
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .revoke_user_sessions(revoke_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success_with_msg(grpc_response.result))
+}
@@ -1,2 +1,6 @@
+pub mod email;
 pub mod login;
+pub mod logout;
+pub mod password_reset;
+pub mod refresh;
 pub mod register;
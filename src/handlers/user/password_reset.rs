@@ -0,0 +1,46 @@
+use axum::{debug_handler, extract::State};
+
+use crate::{
+    common::{client_info::ClientInfo, valid::ValidJson},
+    handlers::common::model::{ConfirmPasswordResetParam, RequestPasswordResetParam},
+    pb::user::{ConfirmPasswordResetRequest, RequestPasswordResetRequest},
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn request_password_reset_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    client_info: ClientInfo,
+    ValidJson(params): ValidJson<RequestPasswordResetParam>,
+) -> ApiResult<ApiResponse<()>> {
+    let mut reset_request = tonic::Request::new(RequestPasswordResetRequest::from(params));
+    // 转发客户端 IP，按客户端而不是网关的 IP 限制申请次数
+    client_info.forward(&mut reset_request);
+    // 无论帐号是否存在都返回同样的结果
+    let mut client = grpc_factory.create_client().await?;
+    // 错误码和 HTTP 状态码由 gRPC 错误决定
+    let grpc_response = client
+        .request_password_reset(reset_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success_with_msg(grpc_response.result))
+}
+
+#[debug_handler]
+pub async fn confirm_password_reset_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    ValidJson(params): ValidJson<ConfirmPasswordResetParam>,
+) -> ApiResult<ApiResponse<()>> {
+    let confirm_request: ConfirmPasswordResetRequest = params.into();
+    // 使用邮件中的令牌重置密码，全部会话随之失效
+    let mut client = grpc_factory.create_client().await?;
+    // 错误码和 HTTP 状态码由 gRPC 错误决定
+    let grpc_response = client
+        .confirm_password_reset(confirm_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success_with_msg(grpc_response.result))
+}
@@ -0,0 +1,25 @@
+use axum::{debug_handler, extract::State};
+
+use crate::{
+    common::valid::ValidJson,
+    handlers::common::model::RefreshTokenParam,
+    pb::user::{RefreshTokenRequest, UserLoginResponse},
+    response::{ApiResult, errors::ApiError, resp::ApiResponse},
+    state::app_state::AppState,
+};
+
+#[debug_handler]
+pub async fn refresh_token_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+    ValidJson(params): ValidJson<RefreshTokenParam>,
+) -> ApiResult<ApiResponse<UserLoginResponse>> {
+    let refresh_token_request: RefreshTokenRequest = params.into();
+    // 使用 refresh_token 换取新的 token
+    let mut client = grpc_factory.create_client().await?;
+    let grpc_response = client
+        .refresh_token(refresh_token_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
+    Ok(ApiResponse::success(grpc_response))
+}
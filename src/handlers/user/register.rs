@@ -3,7 +3,7 @@ use axum::{debug_handler, extract::State};
 use crate::{
     common::valid::ValidJson,
     handlers::common::model::{RegisterResult, RegisterUserParam},
-    pb::user::{UserExistsRequest, UserRegisterRequest},
+    pb::user::UserRegisterRequest,
     response::{ApiResult, errors::ApiError, resp::ApiResponse},
     state::app_state::AppState,
 };
@@ -15,25 +15,16 @@ pub async fn user_register_handler(
     // ConnectInfo(_addr): ConnectInfo<SocketAddr>,
     ValidJson(params): ValidJson<RegisterUserParam>,
 ) -> ApiResult<ApiResponse<RegisterResult>> {
-    // 查询用户名是否已经存在
+    // 用户名或邮箱已被使用时服务端返回 AlreadyExists，不再预先查询用户名是否存在
     let mut client = grpc_factory.create_client().await?;
-    let username = UserExistsRequest {
-        username: params.username.clone(),
-    };
-    let exists = client.user_exists(username).await?;
-    // 如果用户名存在就返回
-    if exists.into_inner().exists {
-        return Err(ApiError::Biz(String::from("要注册的帐号已经存在!")));
-    }
     // 转换成 UserRegisterRequest
     let register_request: UserRegisterRequest = params.into();
-    let grpc_response = match client.user_register(register_request).await {
-        Ok(response) => response.into_inner(),
-        Err(status) => {
-            tracing::error!("grpc error: {:?}", status);
-            return Err(ApiError::GrpcError(status));
-        }
-    };
+    // 错误码和 HTTP 状态码由 gRPC 错误决定
+    let grpc_response = client
+        .user_register(register_request)
+        .await
+        .map_err(ApiError::from)?
+        .into_inner();
     Ok(ApiResponse::success(RegisterResult {
         result: grpc_response.result,
     }))
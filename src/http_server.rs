@@ -1,4 +1,8 @@
-use user_server::{app, conf, log};
+use user_server::{
+    app, conf, db,
+    log::{self, otel::init_tracer_provider},
+    middlewares::auth::jwt::init_global_jwt,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -6,8 +10,14 @@ async fn main() -> anyhow::Result<()> {
     let config = conf::get_app_config();
     // 2. 初始化日志，为了防止多线程日志写入不完整，要保留 guard，main 函数结束时释放
     let log_level = config.http_config().log_level();
-    let _guard = log::logger::init_logger_with_file(log_level).await?;
-    // 3. 服务地址
+    let tracer_provider = init_tracer_provider(config.otel(), "user-server-http")?;
+    let _guard = log::logger::init_logger_with_file(log_level, &tracer_provider).await?;
+    // 初始化 JWT，用于校验 token
+    init_global_jwt(config.jwt(), config.is_dev())?;
+    // 3. 初始化 Redis 连接池，用于校验 token 是否被吊销
+    let redis = db::redis::init_redis_pool_with_config(config.redis()).await?;
+    db::set_global_redis(redis).await?;
+    // 4. 服务地址
     let mut grpc_addr = format!(
         "http://{}:{}",
         config.grpc_config().name(),
@@ -17,10 +27,13 @@ async fn main() -> anyhow::Result<()> {
         grpc_addr = format!("http://[::1]:{}", config.grpc_config().port());
     }
     tracing::info!("grpc_addr:{}", grpc_addr);
-    // 4. 启动服务
+    // 5. 启动服务
     app::server::Server::new(config)
         .start_server(&grpc_addr)
         .await?;
-
+    // 6. 发送剩余的链路追踪数据
+    if let Err(e) = tracer_provider.shutdown() {
+        tracing::warn!("关闭链路追踪失败: {:?}", e);
+    }
     Ok(())
 }
@@ -5,6 +5,8 @@ pub mod db;
 pub mod factory;
 pub mod handlers;
 pub mod log;
+pub mod mail;
+pub mod metrics;
 pub mod middlewares;
 pub mod pb;
 pub mod response;
@@ -1,14 +1,20 @@
 use crate::utils::timezone::LocalTimer;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::str::FromStr;
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling;
 use tracing_subscriber::{Layer as _, Registry, layer::SubscriberExt, util::SubscriberInitExt};
 
-/// 初始化日志相关，将日志输出到文件中
+/// 初始化日志相关，将日志输出到文件中，同时把 span 交给 OpenTelemetry
 ///
 /// # 参数
 /// - logger_level: 日志等级
-pub async fn init_logger_with_file(logger_level: &str) -> anyhow::Result<WorkerGuard> {
+/// - tracer_provider: 由 `log::otel::init_tracer_provider` 创建
+pub async fn init_logger_with_file(
+    logger_level: &str,
+    tracer_provider: &SdkTracerProvider,
+) -> anyhow::Result<WorkerGuard> {
     // set logger level form params if there had error use default info level
     let level = tracing::level_filters::LevelFilter::from_str(logger_level)
         .unwrap_or(tracing::level_filters::LevelFilter::INFO);
@@ -42,9 +48,15 @@ pub async fn init_logger_with_file(logger_level: &str) -> anyhow::Result<WorkerG
         .with_writer(std::io::stdout)
         .with_filter(level);
 
+    // 链路追踪，span 的上下文通过 traceparent 在服务之间传递
+    let otel_layer = tracing_opentelemetry::layer()
+        .with_tracer(tracer_provider.tracer("user_server"))
+        .with_filter(level);
+
     Registry::default()
         .with(file_layer) // 写入文件
         .with(stdout_layer) // 输出到终端
+        .with(otel_layer) // 链路追踪
         .init();
     Ok(guard)
 }
@@ -0,0 +1,136 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use opentelemetry::{global, trace::Status};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    Resource,
+    error::{OTelSdkError, OTelSdkResult},
+    propagation::TraceContextPropagator,
+    trace::{Sampler, SdkTracerProvider, SpanData, SpanExporter},
+};
+
+use crate::conf::otel::{OtelConfig, OtelExporter};
+
+/// 初始化 OpenTelemetry
+///
+/// # 功能描述
+/// 设置全局的 W3C `traceparent` 传播器，并按配置创建 `SdkTracerProvider`。
+/// 没有开启导出时也会创建（不带导出器），这样链路上下文仍然能在 HTTP 和 gRPC 服务之间传递。
+/// 进程退出前应调用返回值的 `shutdown`，把缓存的 span 发送出去。
+///
+/// # 参数
+/// - `config`: 链路追踪配置
+/// - `service_name`: 上报的服务名，如 `user-server-http`
+///
+/// # 返回值
+/// 返回 `SdkTracerProvider`，通过 `tracer` 方法创建传给日志的 tracer
+pub fn init_tracer_provider(
+    config: &OtelConfig,
+    service_name: &'static str,
+) -> anyhow::Result<SdkTracerProvider> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let builder = SdkTracerProvider::builder()
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio(),
+        ))));
+    if !config.enabled() {
+        return Ok(builder.build());
+    }
+    let timeout = Duration::from_secs(config.export_timeout_secs());
+    let provider = match config.exporter() {
+        OtelExporter::Otlp => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(config.endpoint())
+                .with_timeout(timeout)
+                .build()
+                .map_err(|e| anyhow::anyhow!("创建 OTLP 导出器失败：{}", e))?;
+            builder.with_batch_exporter(exporter).build()
+        }
+        OtelExporter::File => {
+            let exporter = FileSpanExporter::new(config.file_path())?;
+            builder.with_batch_exporter(exporter).build()
+        }
+    };
+    Ok(provider)
+}
+
+/// 把 span 写入本地文件的导出器，每个 span 一行 JSON
+///
+/// # 功能描述
+/// 不依赖 collector，用于本地调试和测试，生产环境应使用 OTLP。
+#[derive(Debug)]
+pub struct FileSpanExporter {
+    file: Mutex<File>,
+}
+
+impl FileSpanExporter {
+    /// 以追加方式打开文件，目录不存在时自动创建
+    pub fn new(path: &str) -> anyhow::Result<Self> {
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| anyhow::anyhow!("打开 span 文件 {} 失败：{}", path, e))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl SpanExporter for FileSpanExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let mut lines = String::new();
+        for span in &batch {
+            lines.push_str(&span_to_json(span).to_string());
+            lines.push('\n');
+        }
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| OTelSdkError::InternalFailure(String::from("span 文件锁已损坏")))?;
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))
+    }
+}
+
+/// 把 span 转换为 JSON，只保留排查问题需要的字段
+fn span_to_json(span: &SpanData) -> serde_json::Value {
+    let attributes = span
+        .attributes
+        .iter()
+        .map(|kv| (kv.key.to_string(), serde_json::json!(kv.value.as_str())))
+        .collect::<serde_json::Map<_, _>>();
+    let status = match &span.status {
+        Status::Unset => String::from("unset"),
+        Status::Ok => String::from("ok"),
+        Status::Error { description } => format!("error: {description}"),
+    };
+    serde_json::json!({
+        "trace_id": span.span_context.trace_id().to_string(),
+        "span_id": span.span_context.span_id().to_string(),
+        "parent_span_id": span.parent_span_id.to_string(),
+        "name": span.name,
+        "kind": format!("{:?}", span.span_kind),
+        "start_time_unix_nano": unix_nanos(span.start_time),
+        "end_time_unix_nano": unix_nanos(span.end_time),
+        "attributes": attributes,
+        "status": status,
+    })
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
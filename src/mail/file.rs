@@ -0,0 +1,34 @@
+use lettre::{AsyncFileTransport, AsyncTransport, Tokio1Executor, message::Mailbox};
+
+use crate::mail::{Mail, Mailer, build_message};
+
+/// 把邮件写入目录中的 .eml 文件，用于本地开发和测试
+#[derive(Debug)]
+pub struct FileMailer {
+    from: Mailbox,
+    transport: AsyncFileTransport<Tokio1Executor>,
+}
+
+impl FileMailer {
+    /// 创建文件邮件发送器，目录不存在时自动创建
+    ///
+    /// # 参数
+    /// - `from`: 发件人
+    /// - `dir`: 邮件的保存目录
+    pub fn new(from: Mailbox, dir: &str) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            from,
+            transport: AsyncFileTransport::new(dir),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, mail: Mail) -> anyhow::Result<()> {
+        let message = build_message(&self.from, mail)?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
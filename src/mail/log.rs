@@ -0,0 +1,18 @@
+use crate::mail::{Mail, Mailer};
+
+/// 只把邮件输出到日志，邮件中的链接可以直接从日志中复制，只用于本地开发
+#[derive(Debug, Default)]
+pub struct LogMailer;
+
+#[async_trait::async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, mail: Mail) -> anyhow::Result<()> {
+        tracing::info!(
+            "mail to: {}, subject: {}\n{}",
+            mail.to,
+            mail.subject,
+            mail.body
+        );
+        Ok(())
+    }
+}
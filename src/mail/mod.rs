@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use lettre::{
+    Message,
+    message::{Mailbox, header::ContentType},
+};
+
+use crate::conf::mail::{MailConfig, MailTransport};
+
+pub mod file;
+pub mod log;
+pub mod smtp;
+
+/// 一封纯文本邮件
+///
+/// # 成员
+/// - to: 收件人地址
+/// - subject: 主题
+/// - body: 正文
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// 邮件发送
+///
+/// 不同的发送方式实现这个 trait，业务代码只依赖 `Arc<dyn Mailer>`
+#[async_trait::async_trait]
+pub trait Mailer: std::fmt::Debug + Send + Sync {
+    async fn send(&self, mail: Mail) -> anyhow::Result<()>;
+}
+
+/// 根据配置创建邮件发送器
+///
+/// # 参数
+/// - `config`: 邮件配置
+///
+/// # 返回值
+/// 返回邮件发送器 `anyhow::Result<Arc<dyn Mailer>>`
+pub fn build_mailer(config: &MailConfig) -> anyhow::Result<Arc<dyn Mailer>> {
+    let from: Mailbox = config
+        .from()
+        .parse()
+        .map_err(|e| anyhow::anyhow!("invalid mail.from {}: {e}", config.from()))?;
+    Ok(match config.transport() {
+        MailTransport::Log => Arc::new(log::LogMailer),
+        MailTransport::File => Arc::new(file::FileMailer::new(from, config.file_dir())?),
+        MailTransport::Smtp => {
+            let smtp = config
+                .smtp()
+                .ok_or_else(|| anyhow::anyhow!("mail.smtp is required for the smtp transport"))?;
+            Arc::new(smtp::SmtpMailer::new(from, smtp)?)
+        }
+    })
+}
+
+/// 把邮件转换成 lettre 的 Message
+fn build_message(from: &Mailbox, mail: Mail) -> anyhow::Result<Message> {
+    Ok(Message::builder()
+        .from(from.clone())
+        .to(mail.to.parse()?)
+        .subject(mail.subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(mail.body)?)
+}
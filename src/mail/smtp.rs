@@ -0,0 +1,44 @@
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Tokio1Executor, message::Mailbox,
+    transport::smtp::authentication::Credentials,
+};
+
+use crate::{
+    conf::{mail::SmtpConfig, secret::resolve_secret},
+    mail::{Mail, Mailer, build_message},
+};
+
+/// 通过 SMTP 发送邮件，使用 STARTTLS 加密连接
+#[derive(Debug)]
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    /// 创建 SMTP 邮件发送器
+    ///
+    /// # 参数
+    /// - `from`: 发件人
+    /// - `config`: SMTP 服务器配置
+    pub fn new(from: Mailbox, config: &SmtpConfig) -> anyhow::Result<Self> {
+        let credentials = Credentials::new(
+            config.username().to_string(),
+            resolve_secret(config.password())?,
+        );
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(config.host())?
+            .port(config.port())
+            .credentials(credentials)
+            .build();
+        Ok(Self { from, transport })
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: Mail) -> anyhow::Result<()> {
+        let message = build_message(&self.from, mail)?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
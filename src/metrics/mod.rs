@@ -0,0 +1,91 @@
+use std::{sync::OnceLock, time::Duration};
+
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+pub mod pool;
+
+/// HTTP 请求数，标签：method、route、status
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+/// HTTP 请求耗时（秒），标签：method、route、status
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+/// gRPC 调用数，标签：grpc_service、grpc_method、grpc_code
+pub const GRPC_SERVER_HANDLED_TOTAL: &str = "grpc_server_handled_total";
+/// gRPC 调用耗时（秒），标签：grpc_service、grpc_method、grpc_code
+pub const GRPC_SERVER_HANDLING_SECONDS: &str = "grpc_server_handling_seconds";
+/// 登录次数，标签：outcome
+pub const USER_LOGINS_TOTAL: &str = "user_logins_total";
+/// 注册次数，标签：result
+pub const USER_REGISTRATIONS_TOTAL: &str = "user_registrations_total";
+/// 连接池中的连接数
+pub const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+/// 连接池中空闲的连接数
+pub const DB_POOL_IDLE_CONNECTIONS: &str = "db_pool_idle_connections";
+/// 连接池允许的最大连接数
+pub const DB_POOL_MAX_CONNECTIONS: &str = "db_pool_max_connections";
+/// 业务代码从连接池获取连接的等待时间（秒）
+pub const DB_POOL_ACQUIRE_WAIT_SECONDS: &str = "db_pool_acquire_wait_seconds";
+
+/// 请求耗时的直方图分桶（秒）
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+/// 获取连接等待时间的直方图分桶（秒）
+const ACQUIRE_BUCKETS: &[f64] = &[0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+/// 清理直方图数据的间隔
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 全局的 Prometheus 句柄，用于输出指标
+static PROMETHEUS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// 安装全局的 Prometheus 指标记录器
+///
+/// # 功能描述
+/// 只会安装一次，重复调用返回同一个句柄。安装后在后台定期清理直方图数据，
+/// 因此必须在 tokio 运行时中调用。没有调用时各处记录指标的代码不会产生任何开销。
+///
+/// # 返回值
+/// 返回用于输出指标的句柄
+pub fn init_metrics() -> anyhow::Result<&'static PrometheusHandle> {
+    if let Some(handle) = PROMETHEUS_HANDLE.get() {
+        return Ok(handle);
+    }
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Suffix("_duration_seconds".to_string()),
+            LATENCY_BUCKETS,
+        )?
+        .set_buckets_for_metric(
+            Matcher::Full(GRPC_SERVER_HANDLING_SECONDS.to_string()),
+            LATENCY_BUCKETS,
+        )?
+        .set_buckets_for_metric(
+            Matcher::Full(DB_POOL_ACQUIRE_WAIT_SECONDS.to_string()),
+            ACQUIRE_BUCKETS,
+        )?
+        .install_recorder()
+        .map_err(|e| anyhow::anyhow!("安装 Prometheus 指标记录器失败：{}", e))?;
+    let handle = PROMETHEUS_HANDLE.get_or_init(|| handle);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UPKEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            handle.run_upkeep();
+        }
+    });
+    Ok(handle)
+}
+
+/// 以 Prometheus 文本格式输出全部指标，没有安装记录器时返回 `None`
+pub fn render() -> Option<String> {
+    PROMETHEUS_HANDLE.get().map(PrometheusHandle::render)
+}
+
+/// 记录一次登录，`outcome` 为 `success` 或失败原因
+pub fn record_login(outcome: &'static str) {
+    metrics::counter!(USER_LOGINS_TOTAL, "outcome" => outcome).increment(1);
+}
+
+/// 记录一次注册，`result` 为 `success`、`conflict` 等
+pub fn record_registration(result: &'static str) {
+    metrics::counter!(USER_REGISTRATIONS_TOTAL, "result" => result).increment(1);
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+
+use crate::metrics::{DB_POOL_CONNECTIONS, DB_POOL_IDLE_CONNECTIONS, DB_POOL_MAX_CONNECTIONS};
+
+/// 定期采集数据库连接池的状态
+///
+/// # 功能描述
+/// 每隔 `interval` 记录一次连接数、空闲连接数和最大连接数，空闲连接数长期为 0 说明连接池繁忙。
+/// 获取连接的等待时间由 [`crate::db::pgsql::acquire`] 在每次获取连接时记录。
+/// 应在服务启动时通过 `tokio::spawn` 在后台运行。
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `interval`: 采集间隔
+pub async fn sample_pool_metrics(pool: &'static PgPool, interval: Duration) {
+    let max_connections = pool.options().get_max_connections();
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        metrics::gauge!(DB_POOL_MAX_CONNECTIONS).set(max_connections as f64);
+        metrics::gauge!(DB_POOL_CONNECTIONS).set(pool.size() as f64);
+        metrics::gauge!(DB_POOL_IDLE_CONNECTIONS).set(pool.num_idle() as f64);
+    }
+}
@@ -1,11 +1,16 @@
-use crate::middlewares::auth::jwt::{JWT, get_default_jwt};
+use crate::middlewares::auth::jwt::{JWT, get_global_jwt};
+use crate::middlewares::auth::{principal::Principal, revocation};
 use crate::response::errors::ApiError;
-use axum::http::{Request, Response};
+use axum::http::{HeaderMap, Request, Response};
 use std::sync::LazyLock;
 use tower_http::auth::{AsyncAuthorizeRequest, AsyncRequireAuthorizationLayer};
 
 static AUTH_LAYER: LazyLock<AsyncRequireAuthorizationLayer<JwtAuth>> =
-    LazyLock::new(|| AsyncRequireAuthorizationLayer::new(JwtAuth::new(get_default_jwt())));
+    LazyLock::new(|| AsyncRequireAuthorizationLayer::new(JwtAuth::new(get_global_jwt())));
+
+/// 通过认证的原始 access token，供需要转发 token 的 handler 使用（如退出登录）
+#[derive(Debug, Clone)]
+pub struct AccessToken(pub String);
 
 /// JwtAuth struct
 #[derive(Clone)]
@@ -35,39 +40,72 @@ impl AsyncAuthorizeRequest<axum::body::Body> for JwtAuth {
     fn authorize(&mut self, mut request: Request<axum::body::Body>) -> Self::Future {
         let jwt = self.jwt;
         Box::pin(async move {
-            // get the token from request header
-            let token = request
-                .headers()
-                .get(axum::http::header::AUTHORIZATION)
-                .map(|value| -> Result<&str, ApiError> {
-                    let token = value
-                        .to_str()
-                        .map_err(|_| {
-                            ApiError::Unauthenticated(String::from(
-                                "Authorization 请求头不是一个有效的字符串",
-                            ))
-                        })?
-                        .strip_prefix("Bearer ")
-                        .ok_or_else(|| {
-                            ApiError::Unauthenticated(String::from(
-                                "Authorization 请求头必须以 Bearer 开头!",
-                            ))
-                        })?;
-                    Ok(token)
-                })
-                .transpose()?
-                .ok_or_else(|| {
-                    ApiError::Unauthenticated(String::from("请求头中没有 Authorization 字段"))
-                })?;
-            let principal = jwt
-                .decode(token)
-                .map_err(|err| ApiError::Unauthenticated(format!("没有登陆或登陆已过期 {err}")))?;
+            let (principal, token) = authenticate(jwt, request.headers()).await?;
             request.extensions_mut().insert(principal);
+            request.extensions_mut().insert(token);
             Ok(request)
         })
     }
 }
 
+/// 校验请求头中的 Bearer token
+///
+/// # 功能描述
+/// 从 Authorization 请求头中取出 Bearer token，校验签名和有效期，并确认 token 没有被吊销。
+/// HTTP 的 `JwtAuth` 和 gRPC 的认证层共用这一套逻辑。
+///
+/// # 参数
+/// - `jwt`: 用于校验的 JWT
+/// - `headers`: 请求头（gRPC 的 metadata 也是 HTTP/2 请求头）
+///
+/// # 返回值
+/// 返回 token 中的 `Principal` 和原始的 `AccessToken`
+pub async fn authenticate(
+    jwt: &JWT,
+    headers: &HeaderMap,
+) -> Result<(Principal, AccessToken), ApiError> {
+    // get the token from request header
+    let token = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .map(|value| -> Result<&str, ApiError> {
+            let token = value
+                .to_str()
+                .map_err(|_| {
+                    ApiError::Unauthenticated(String::from(
+                        "Authorization 请求头不是一个有效的字符串",
+                    ))
+                })?
+                .strip_prefix("Bearer ")
+                .ok_or_else(|| {
+                    ApiError::Unauthenticated(String::from(
+                        "Authorization 请求头必须以 Bearer 开头!",
+                    ))
+                })?;
+            Ok(token)
+        })
+        .transpose()?
+        .ok_or_else(|| {
+            ApiError::Unauthenticated(String::from("请求头中没有 Authorization 字段"))
+        })?;
+    let (claims, principal) = jwt
+        .decode_claims(token)
+        .and_then(|claims| claims.principal().map(|principal| (claims, principal)))
+        .map_err(|err| ApiError::Unauthenticated(format!("没有登陆或登陆已过期 {err}")))?;
+    // check the token has not been revoked
+    let revoked = revocation::is_revoked(&claims, principal.id)
+        .await
+        .map_err(|err| {
+            tracing::error!("failed to check token revocation: {:?}", err);
+            ApiError::InternalServerError
+        })?;
+    if revoked {
+        return Err(ApiError::Unauthenticated(String::from(
+            "登录已失效，请重新登录！",
+        )));
+    }
+    Ok((principal, AccessToken(token.to_string())))
+}
+
 /// public method to get the static AUTH_LAYER pointer
 pub fn get_auth_layer() -> &'static AsyncRequireAuthorizationLayer<JwtAuth> {
     &AUTH_LAYER
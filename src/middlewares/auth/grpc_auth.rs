@@ -0,0 +1,122 @@
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tonic::Status;
+use tower::{Layer, Service};
+
+use crate::{
+    middlewares::auth::{auth_layer::authenticate, jwt::JWT},
+    response::errors::ApiError,
+};
+
+/// gRPC 认证层
+///
+/// # 功能描述
+/// 校验 `authorization` metadata 中的 Bearer token，通过后把 `Principal` 和 `AccessToken`
+/// 放入请求扩展，服务实现中可以通过 `request.extensions()` 取出。
+/// 通过 `with_public_methods` 标记的方法不需要认证。
+///
+/// # 成员
+/// - jwt: 用于校验 token 的 JWT
+/// - public_methods: 公开方法的完整路径，如 `/user.UserService/UserLogin`
+#[derive(Clone)]
+pub struct GrpcAuthLayer {
+    jwt: &'static JWT,
+    public_methods: Arc<HashSet<String>>,
+}
+
+impl GrpcAuthLayer {
+    pub fn new(jwt: &'static JWT) -> Self {
+        Self {
+            jwt,
+            public_methods: Arc::new(HashSet::new()),
+        }
+    }
+
+    /// 标记不需要认证的方法
+    ///
+    /// # 参数
+    /// - `service`: 服务的完整名称，如 `user.UserService`
+    /// - `methods`: 方法名称，如 `UserLogin`
+    pub fn with_public_methods<'a>(
+        mut self,
+        service: &str,
+        methods: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let public_methods = Arc::make_mut(&mut self.public_methods);
+        for method in methods {
+            public_methods.insert(format!("/{service}/{method}"));
+        }
+        self
+    }
+}
+
+impl<S> Layer<S> for GrpcAuthLayer {
+    type Service = GrpcAuth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcAuth {
+            inner,
+            jwt: self.jwt,
+            public_methods: self.public_methods.clone(),
+        }
+    }
+}
+
+/// gRPC 认证服务
+#[derive(Clone)]
+pub struct GrpcAuth<S> {
+    inner: S,
+    jwt: &'static JWT,
+    public_methods: Arc<HashSet<String>>,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for GrpcAuth<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        // 取出已经 ready 的 inner，留一个 clone 给下一次调用
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        // 公开方法直接放行
+        if self.public_methods.contains(request.uri().path()) {
+            return Box::pin(inner.call(request));
+        }
+        let jwt = self.jwt;
+        Box::pin(async move {
+            match authenticate(jwt, request.headers()).await {
+                Ok((principal, token)) => {
+                    request.extensions_mut().insert(principal);
+                    request.extensions_mut().insert(token);
+                    inner.call(request).await
+                }
+                Err(err) => Ok(to_status(err).into_http()),
+            }
+        })
+    }
+}
+
+/// 认证失败的错误转换为 gRPC 状态
+fn to_status(err: ApiError) -> Status {
+    match err {
+        ApiError::Unauthenticated(message) => Status::unauthenticated(message),
+        _ => Status::internal("服务器内部错误"),
+    }
+}
@@ -0,0 +1,143 @@
+use std::marker::PhantomData;
+
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::request::Parts,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    middlewares::auth::{identity::Identity, principal::Principal},
+    response::errors::ApiError,
+};
+
+/// 身份等级标记 trait，用于在类型上声明最低身份要求
+pub trait IdentityLevel: Send + Sync + 'static {
+    /// 要求的最低身份
+    const MIN: Identity;
+}
+
+/// 身份等级标记类型
+pub mod level {
+    use super::IdentityLevel;
+    use crate::middlewares::auth::identity::Identity;
+
+    /// 至少为 Guest
+    pub struct Guest;
+    /// 至少为 Member
+    pub struct Member;
+    /// 至少为 Vip
+    pub struct Vip;
+    /// 必须为 Admin
+    pub struct Admin;
+
+    impl IdentityLevel for Guest {
+        const MIN: Identity = Identity::Guest;
+    }
+    impl IdentityLevel for Member {
+        const MIN: Identity = Identity::Member;
+    }
+    impl IdentityLevel for Vip {
+        const MIN: Identity = Identity::Vip;
+    }
+    impl IdentityLevel for Admin {
+        const MIN: Identity = Identity::Admin;
+    }
+}
+
+/// 要求最低身份等级的提取器
+///
+/// # 功能描述
+/// 从请求扩展中读取 `JwtAuth` 插入的 `Principal`，身份不足时返回 403。
+/// 必须放在 `get_auth_layer` 保护的路由下使用。
+///
+/// # 示例
+/// ```
+/// use user_server::middlewares::auth::guard::{RequireIdentity, level};
+///
+/// async fn handler(RequireIdentity(principal, ..): RequireIdentity<level::Admin>) -> String {
+///     principal.username
+/// }
+/// let router: axum::Router = axum::Router::new().route("/users", axum::routing::get(handler));
+/// ```
+pub struct RequireIdentity<L: IdentityLevel>(pub Principal, pub PhantomData<L>);
+
+impl<L: IdentityLevel> RequireIdentity<L> {
+    /// 获取通过校验的 principal
+    pub fn into_principal(self) -> Principal {
+        self.0
+    }
+}
+
+impl<S, L> FromRequestParts<S> for RequireIdentity<L>
+where
+    S: Send + Sync,
+    L: IdentityLevel,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let principal = parts
+            .extensions
+            .get::<Principal>()
+            .ok_or_else(|| ApiError::Unauthenticated(String::from("没有登陆或登陆已过期")))?;
+        check_identity(principal, &L::MIN).map_err(ApiError::Forbidden)?;
+        Ok(RequireIdentity(principal.clone(), PhantomData))
+    }
+}
+
+/// 要求最低身份等级的中间件，用于整组路由
+///
+/// # 示例
+/// ```
+/// use axum::{Router, middleware, routing::get};
+/// use user_server::middlewares::auth::{
+///     auth_layer::get_auth_layer,
+///     guard::{level, require_identity},
+/// };
+///
+/// fn admin_router() -> Router {
+///     Router::new()
+///         .route("/users", get(|| async { "ok" }))
+///         .route_layer(middleware::from_fn(require_identity::<level::Admin>))
+///         .route_layer(get_auth_layer().clone())
+/// }
+/// ```
+pub async fn require_identity<L: IdentityLevel>(
+    _guard: RequireIdentity<L>,
+    request: Request,
+    next: Next,
+) -> Response {
+    next.run(request).await
+}
+
+/// gRPC 服务中校验最低身份等级
+///
+/// # 功能描述
+/// 从 tonic 请求扩展中读取认证拦截器插入的 `Principal`，
+/// 未登录返回 `Unauthenticated`，身份不足返回 `PermissionDenied`。
+///
+/// # 参数
+/// - `request`: tonic 请求
+/// - `min`: 要求的最低身份
+pub fn require_grpc_identity<T>(
+    request: &tonic::Request<T>,
+    min: &Identity,
+) -> Result<Principal, tonic::Status> {
+    let principal = request
+        .extensions()
+        .get::<Principal>()
+        .ok_or_else(|| tonic::Status::unauthenticated("没有登陆或登陆已过期"))?;
+    check_identity(principal, min).map_err(tonic::Status::permission_denied)?;
+    Ok(principal.clone())
+}
+
+/// 校验身份等级，失败时返回错误信息
+fn check_identity(principal: &Principal, min: &Identity) -> Result<(), String> {
+    if principal.has_identity(min) {
+        Ok(())
+    } else {
+        Err(format!("需要 {} 及以上的身份", min.as_str()))
+    }
+}
@@ -6,7 +6,8 @@ use sqlx::{
     prelude::Type,
 };
 
-#[derive(Debug, Clone, PartialEq)]
+/// 用户身份等级，按声明顺序从低到高排列：Guest < Member < Vip < Admin
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Identity {
     Guest,
     Member,
@@ -92,11 +93,16 @@ impl Identity {
         }
     }
     pub(crate) fn from_str(s: &str) -> Self {
+        Self::parse(s).unwrap_or(Identity::Guest)
+    }
+    /// 严格解析身份等级，无法识别时返回 None
+    pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
-            "member" => Identity::Member,
-            "vip" => Identity::Vip,
-            "admin" => Identity::Admin,
-            _ => Identity::Guest,
+            "guest" => Some(Identity::Guest),
+            "member" => Some(Identity::Member),
+            "vip" => Some(Identity::Vip),
+            "admin" => Some(Identity::Admin),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,139 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, DecodingKeyKind,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+        EllipticCurveKeyType, Jwk, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType,
+        PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+};
+use sha2::{Digest, Sha256};
+
+/// 根据公钥生成 JWK
+///
+/// # 功能描述
+/// 把从 PEM 读取的公钥转换成 JWK，供 `/.well-known/jwks.json` 对外公布。
+/// 只包含公钥参数，其他服务拿到后只能校验 token，不能签发 token。
+///
+/// # 参数
+/// - `key`: 公钥
+/// - `algorithm`: 签名算法
+/// - `kid`: 密钥 id
+///
+/// # 返回值
+/// 返回 JWK `anyhow::Result<Jwk>`
+pub(crate) fn public_jwk(
+    key: &DecodingKey,
+    algorithm: Algorithm,
+    kid: &str,
+) -> anyhow::Result<Jwk> {
+    let bytes = public_key_bytes(key)?;
+    let parameters = match algorithm {
+        Algorithm::RS256
+        | Algorithm::RS384
+        | Algorithm::RS512
+        | Algorithm::PS256
+        | Algorithm::PS384
+        | Algorithm::PS512 => {
+            let (n, e) = parse_rsa_public_key(bytes)?;
+            AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(n),
+                e: URL_SAFE_NO_PAD.encode(e),
+            })
+        }
+        Algorithm::ES256 | Algorithm::ES384 => {
+            // 未压缩的椭圆曲线点：0x04 || x || y
+            let point = bytes
+                .strip_prefix(&[0x04])
+                .ok_or_else(|| anyhow::anyhow!("unsupported ec public key format"))?;
+            let (x, y) = point.split_at(point.len() / 2);
+            let curve = match algorithm {
+                Algorithm::ES256 => EllipticCurve::P256,
+                _ => EllipticCurve::P384,
+            };
+            AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                key_type: EllipticCurveKeyType::EC,
+                curve,
+                x: URL_SAFE_NO_PAD.encode(x),
+                y: URL_SAFE_NO_PAD.encode(y),
+            })
+        }
+        Algorithm::EdDSA => AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+            key_type: OctetKeyPairType::OctetKeyPair,
+            curve: EllipticCurve::Ed25519,
+            x: URL_SAFE_NO_PAD.encode(bytes),
+        }),
+        _ => anyhow::bail!("{algorithm:?} keys can not be published"),
+    };
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(format!("{algorithm:?}").parse::<KeyAlgorithm>()?),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
+
+/// 根据公钥生成默认的密钥 id，取公钥 SHA-256 摘要的前 16 位
+pub(crate) fn default_kid(key: &DecodingKey) -> anyhow::Result<String> {
+    let digest = Sha256::digest(public_key_bytes(key)?);
+    Ok(hex::encode(&digest[..8]))
+}
+
+/// 取出公钥的原始字节
+fn public_key_bytes(key: &DecodingKey) -> anyhow::Result<&[u8]> {
+    match key.kind() {
+        DecodingKeyKind::SecretOrDer(bytes) => Ok(bytes),
+        DecodingKeyKind::RsaModulusExponent { .. } => {
+            anyhow::bail!("rsa public key components are not supported")
+        }
+    }
+}
+
+/// 解析 PKCS#1 的 RSAPublicKey：SEQUENCE { INTEGER n, INTEGER e }
+fn parse_rsa_public_key(der: &[u8]) -> anyhow::Result<(&[u8], &[u8])> {
+    let (sequence, _) = read_der(der, 0x30)?;
+    let (n, rest) = read_der(sequence, 0x02)?;
+    let (e, _) = read_der(rest, 0x02)?;
+    Ok((strip_leading_zeros(n), strip_leading_zeros(e)))
+}
+
+/// 读取一个 DER 元素，返回它的内容和剩余的字节
+fn read_der(input: &[u8], tag: u8) -> anyhow::Result<(&[u8], &[u8])> {
+    let invalid = || anyhow::anyhow!("invalid rsa public key");
+    let (&actual, input) = input.split_first().ok_or_else(invalid)?;
+    if actual != tag {
+        return Err(invalid());
+    }
+    let (&first, mut input) = input.split_first().ok_or_else(invalid)?;
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        // 长格式：低 7 位表示长度占用的字节数
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > std::mem::size_of::<usize>() || input.len() < count {
+            return Err(invalid());
+        }
+        let (len_bytes, rest) = input.split_at(count);
+        input = rest;
+        len_bytes
+            .iter()
+            .fold(0usize, |len, &byte| (len << 8) | byte as usize)
+    };
+    if input.len() < len {
+        return Err(invalid());
+    }
+    Ok(input.split_at(len))
+}
+
+/// 去掉 DER 整数前面用于表示正数的 0
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|&byte| byte != 0)
+        .unwrap_or(bytes.len().saturating_sub(1));
+    &bytes[start..]
+}
@@ -1,17 +1,45 @@
-use std::sync::LazyLock;
+use std::sync::OnceLock;
 
-use crate::middlewares::auth::{identity::Identity, principal::Principal};
+use crate::{
+    conf::{
+        jwt::{DEFAULT_SECRET, JwtConfig},
+        secret::resolve_secret,
+    },
+    middlewares::auth::{identity::Identity, jwks, principal::Principal},
+};
 
-/// define the default secret
-const DEFAULT_SECRET: &str = "WK2953aOagwo2SwUye";
 /// define the static JWT for global
-static DEFAULT_JWT: LazyLock<JWT> = LazyLock::new(JWT::default);
+static GLOBAL_JWT: OnceLock<JWT> = OnceLock::new();
 
-const EXPIRATION_TIME: u64 = 60 * 60 * 24 * 30;
+/// get global JWT pointer
+pub fn get_global_jwt() -> &'static JWT {
+    GLOBAL_JWT.get().expect("jwt lost")
+}
+
+/// 使用配置初始化全局的 JWT
+///
+/// # 功能描述
+/// 非开发环境下禁止使用默认密钥，避免所有部署共用同一个公开的密钥。
+///
+/// # 参数
+/// - `config`: JWT 配置
+/// - `is_dev`: 是否为开发环境
+pub fn init_global_jwt(config: &JwtConfig, is_dev: bool) -> anyhow::Result<()> {
+    let jwt = JWT::new(config)?;
+    if jwt.uses_default_secret && !is_dev {
+        anyhow::bail!("the default jwt secret is not allowed in non-dev mode, set jwt.secret");
+    }
+    GLOBAL_JWT
+        .set(jwt)
+        .map_err(|_| anyhow::anyhow!("failed to set global jwt"))
+}
 
-/// get default global JWT pointer
-pub fn get_default_jwt() -> &'static JWT {
-    &DEFAULT_JWT
+/// 当前的 Unix 时间戳（毫秒）
+pub fn current_timestamp_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
 }
 
 /// JWT payload info
@@ -23,61 +51,118 @@ pub struct Claims {
     iss: String, // issuer signatory
     iat: u64,    // issued at
     exp: u64,    // expiration time
+    // issued at in milliseconds, used to compare with the session revocation time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    iat_ms: Option<u64>,
 }
 
-/// JWT generated and authenticated config
-#[derive(Debug)]
-pub struct JwtConfig {
-    pub secret: std::borrow::Cow<'static, str>, // the secret key
-    pub expiration: std::time::Duration,        // expiration time
-    pub audience: String,                       // receiver
-    pub issuer: String,                         // issuer
-}
-
-/// JwtConfig default impl
-impl Default for JwtConfig {
-    fn default() -> Self {
-        Self {
-            secret: std::borrow::Cow::Borrowed(DEFAULT_SECRET),
-            expiration: std::time::Duration::from_secs(EXPIRATION_TIME),
-            audience: "audience".to_string(),
-            issuer: "issuer".to_string(),
-        }
-    }
-}
 /// JwtAuth generation and authenticated more infos
 pub struct JWT {
-    encoding_key: jsonwebtoken::EncodingKey, // encoding secret
-    decoding_key: jsonwebtoken::DecodingKey, // decoding secret
-    header: jsonwebtoken::Header,            // header
+    encoding_key: Option<jsonwebtoken::EncodingKey>, // encoding key, none for verify only
+    header: jsonwebtoken::Header,                    // header
+    keys: Vec<VerificationKey>,                      // key ring, the first is the signing key
+    expiration: std::time::Duration,                 // access token expiration time
+    refresh_expiration: std::time::Duration,         // refresh token expiration time
+    audience: String,                                // receiver
+    issuer: String,                                  // issuer
+    uses_default_secret: bool,                       // whether the default secret is used
+}
+
+/// 校验 token 使用的密钥
+struct VerificationKey {
+    kid: Option<String>,                     // key id
+    decoding_key: jsonwebtoken::DecodingKey, // decoding key
     validation: jsonwebtoken::Validation,    // validation rules
-    expiration: std::time::Duration,         // expiration time
-    audience: String,                        // receiver
-    issuer: String,                          // issuer
+    jwk: Option<jsonwebtoken::jwk::Jwk>,     // published public key
+    expires_at: Option<u64>,                 // no longer accepted after this timestamp
+    uses_default_secret: bool,               // whether the default secret is used
 }
 
 /// JwtAuth new encode and decode methods
 impl JWT {
-    pub fn new(config: JwtConfig) -> Self {
-        let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
-        validation.set_audience(&[&config.audience]);
-        validation.set_issuer(&[&config.issuer]);
-        validation.set_required_spec_claims(&["jti", "sub", "aud", "iss", "iat", "exp"]);
-        let secret = config.secret.as_bytes();
-        Self {
-            encoding_key: jsonwebtoken::EncodingKey::from_secret(secret),
-            decoding_key: jsonwebtoken::DecodingKey::from_secret(secret),
-            header: jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256),
-            validation,
-            expiration: config.expiration,
-            audience: config.audience,
-            issuer: config.issuer,
+    /// 根据配置创建 JWT
+    ///
+    /// # 功能描述
+    /// HMAC 算法使用共享的 secret 签名和校验；非对称算法使用 PEM 格式的私钥签名、公钥校验，
+    /// 并生成对应的 JWK 供其他服务校验 token。
+    /// 当前的签名密钥和 `previous_keys` 中的旧密钥组成密钥环，校验时按 token header 中的 kid 选择密钥，
+    /// 所以轮换密钥后，旧密钥签发且尚未过期的 token 仍然有效。
+    ///
+    /// # 参数
+    /// - `config`: JWT 配置
+    pub fn new(config: &JwtConfig) -> anyhow::Result<Self> {
+        let algorithm = parse_algorithm(config.algorithm())?;
+        let current = VerificationKey::new(
+            config,
+            algorithm,
+            config.kid(),
+            Some(config.secret()),
+            config.public_key(),
+            None,
+        )?;
+        let encoding_key = match (is_hmac(algorithm), config.private_key()) {
+            (true, _) => Some(jsonwebtoken::EncodingKey::from_secret(
+                resolve_secret(config.secret())?.as_bytes(),
+            )),
+            (false, Some(private_key)) => Some(encoding_key_from_pem(
+                algorithm,
+                &resolve_secret(private_key)?,
+            )?),
+            (false, None) => None,
+        };
+        let mut header = jsonwebtoken::Header::new(algorithm);
+        header.kid = current.kid.clone();
+        let mut keys = vec![current];
+        for key in config.previous_keys() {
+            let key = VerificationKey::new(
+                config,
+                parse_algorithm(key.algorithm())?,
+                key.kid(),
+                key.secret(),
+                key.public_key(),
+                key.expires_at(),
+            )?;
+            if keys.iter().any(|other| other.kid == key.kid) {
+                anyhow::bail!("duplicate jwt kid: {:?}", key.kid);
+            }
+            keys.push(key);
         }
+        Ok(Self {
+            encoding_key,
+            header,
+            uses_default_secret: keys.iter().any(|key| key.uses_default_secret),
+            keys,
+            expiration: std::time::Duration::from_secs(config.expiration_secs()),
+            refresh_expiration: std::time::Duration::from_secs(config.refresh_expiration_secs()),
+            audience: config.audience().to_string(),
+            issuer: config.issuer().to_string(),
+        })
+    }
+    /// 对外公布的公钥集合，包含当前和未过期的旧公钥，HMAC 算法的密钥不会公布
+    pub fn jwks(&self) -> jsonwebtoken::jwk::JwkSet {
+        let now = jsonwebtoken::get_current_timestamp();
+        jsonwebtoken::jwk::JwkSet {
+            keys: self
+                .keys
+                .iter()
+                .filter(|key| !key.is_expired(now))
+                .filter_map(|key| key.jwk.clone())
+                .collect(),
+        }
+    }
+    /// access token expiration time
+    pub fn expiration(&self) -> std::time::Duration {
+        self.expiration
+    }
+    /// refresh token expiration time
+    pub fn refresh_expiration(&self) -> std::time::Duration {
+        self.refresh_expiration
     }
     /// encode
     pub fn encode(&self, principal: Principal) -> anyhow::Result<String> {
-        // get the current timestamp use jsonwebtoken method
-        let current_timestamp = jsonwebtoken::get_current_timestamp();
+        // get the current timestamp, seconds for the standard claims
+        let issued_at_millis = current_timestamp_millis();
+        let current_timestamp = issued_at_millis / 1000;
         // create the claims use principal
         let claims = Claims {
             jti: xid::new().to_string(),
@@ -91,13 +176,15 @@ impl JWT {
             iss: self.issuer.clone(),
             iat: current_timestamp,
             exp: current_timestamp.saturating_add(self.expiration.as_secs()),
+            iat_ms: Some(issued_at_millis),
         };
+        // verify only instance has no private key
+        let encoding_key = self
+            .encoding_key
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("jwt private key is not configured"))?;
         // return the encoding result
-        Ok(jsonwebtoken::encode(
-            &self.header,
-            &claims,
-            &self.encoding_key,
-        )?)
+        Ok(jsonwebtoken::encode(&self.header, &claims, encoding_key)?)
     }
 
     /// decode token
@@ -111,18 +198,165 @@ impl JWT {
     /// # 返回值
     /// 返回 Principal `anyhow::Result<Principal>`
     pub fn decode(&self, token: &str) -> anyhow::Result<Principal> {
+        self.decode_claims(token)?.principal()
+    }
+
+    /// decode token claims
+    ///
+    /// # 功能描述
+    /// 校验 token 的签名、过期时间、audience 和 issuer，返回完整的 Claims，
+    /// 用于需要 jti、iat、exp 等信息的场景（如吊销检查、退出登录）。
+    ///
+    /// # 参数
+    /// - `token`： token 字符串引用
+    ///
+    /// # 返回值
+    /// 返回 Claims `anyhow::Result<Claims>`
+    pub fn decode_claims(&self, token: &str) -> anyhow::Result<Claims> {
+        // select the key by kid, the token without kid falls back to the signing key
+        let header = jsonwebtoken::decode_header(token)?;
+        let key = self
+            .keys
+            .iter()
+            .find(|key| key.kid == header.kid)
+            .or_else(|| header.kid.is_none().then(|| &self.keys[0]))
+            .ok_or_else(|| anyhow::anyhow!("unknown jwt kid: {:?}", header.kid))?;
+        if key.is_expired(jsonwebtoken::get_current_timestamp()) {
+            anyhow::bail!("jwt key has expired: {:?}", header.kid);
+        }
         // decoded if had not erred returned the claims
-        let claims: Claims =
-            jsonwebtoken::decode(token, &self.decoding_key, &self.validation)?.claims;
+        Ok(jsonwebtoken::decode(token, &key.decoding_key, &key.validation)?.claims)
+    }
+}
+
+impl VerificationKey {
+    /// 创建校验密钥，HMAC 算法使用 secret，非对称算法使用 PEM 公钥
+    fn new(
+        config: &JwtConfig,
+        algorithm: jsonwebtoken::Algorithm,
+        kid: Option<&str>,
+        secret: Option<&str>,
+        public_key: Option<&str>,
+        expires_at: Option<u64>,
+    ) -> anyhow::Result<Self> {
+        let mut validation = jsonwebtoken::Validation::new(algorithm);
+        validation.set_audience(&[config.audience()]);
+        validation.set_issuer(&[config.issuer()]);
+        validation.set_required_spec_claims(&["jti", "sub", "aud", "iss", "iat", "exp"]);
+        if is_hmac(algorithm) {
+            let secret = resolve_secret(secret.unwrap_or_default())?;
+            if secret.is_empty() {
+                anyhow::bail!("jwt secret must not be empty");
+            }
+            return Ok(Self {
+                kid: kid.map(String::from),
+                decoding_key: jsonwebtoken::DecodingKey::from_secret(secret.as_bytes()),
+                validation,
+                jwk: None,
+                expires_at,
+                uses_default_secret: secret == DEFAULT_SECRET,
+            });
+        }
+        let public_key = public_key
+            .ok_or_else(|| anyhow::anyhow!("jwt public key is required for {algorithm:?}"))?;
+        let decoding_key = decoding_key_from_pem(algorithm, &resolve_secret(public_key)?)?;
+        let kid = match kid {
+            Some(kid) => kid.to_string(),
+            None => jwks::default_kid(&decoding_key)?,
+        };
+        Ok(Self {
+            jwk: Some(jwks::public_jwk(&decoding_key, algorithm, &kid)?),
+            kid: Some(kid),
+            decoding_key,
+            validation,
+            expires_at,
+            uses_default_secret: false,
+        })
+    }
+
+    /// 密钥是否已经过期
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// 解析签名算法
+fn parse_algorithm(algorithm: &str) -> anyhow::Result<jsonwebtoken::Algorithm> {
+    algorithm
+        .parse()
+        .map_err(|_| anyhow::anyhow!("unsupported jwt algorithm: {algorithm}"))
+}
+
+/// 是否为 HMAC 算法
+fn is_hmac(algorithm: jsonwebtoken::Algorithm) -> bool {
+    matches!(
+        algorithm,
+        jsonwebtoken::Algorithm::HS256
+            | jsonwebtoken::Algorithm::HS384
+            | jsonwebtoken::Algorithm::HS512
+    )
+}
+
+/// 从 PEM 读取公钥
+fn decoding_key_from_pem(
+    algorithm: jsonwebtoken::Algorithm,
+    pem: &str,
+) -> anyhow::Result<jsonwebtoken::DecodingKey> {
+    let pem = pem.as_bytes();
+    Ok(match algorithm {
+        jsonwebtoken::Algorithm::ES256 | jsonwebtoken::Algorithm::ES384 => {
+            jsonwebtoken::DecodingKey::from_ec_pem(pem)?
+        }
+        jsonwebtoken::Algorithm::EdDSA => jsonwebtoken::DecodingKey::from_ed_pem(pem)?,
+        _ => jsonwebtoken::DecodingKey::from_rsa_pem(pem)?,
+    })
+}
+
+/// 从 PEM 读取私钥
+fn encoding_key_from_pem(
+    algorithm: jsonwebtoken::Algorithm,
+    pem: &str,
+) -> anyhow::Result<jsonwebtoken::EncodingKey> {
+    let pem = pem.as_bytes();
+    Ok(match algorithm {
+        jsonwebtoken::Algorithm::ES256 | jsonwebtoken::Algorithm::ES384 => {
+            jsonwebtoken::EncodingKey::from_ec_pem(pem)?
+        }
+        jsonwebtoken::Algorithm::EdDSA => jsonwebtoken::EncodingKey::from_ed_pem(pem)?,
+        _ => jsonwebtoken::EncodingKey::from_rsa_pem(pem)?,
+    })
+}
+
+/// Claims accessors
+impl Claims {
+    /// jwt id
+    pub fn jti(&self) -> &str {
+        &self.jti
+    }
+    /// issued at
+    pub fn iat(&self) -> u64 {
+        self.iat
+    }
+    /// issued at in milliseconds, falls back to `iat` for tokens without `iat_ms`
+    pub fn iat_millis(&self) -> u64 {
+        self.iat_ms.unwrap_or(self.iat.saturating_mul(1000))
+    }
+    /// expiration time
+    pub fn exp(&self) -> u64 {
+        self.exp
+    }
+    /// parse the subject into principal
+    pub fn principal(&self) -> anyhow::Result<Principal> {
         // split the claims part
-        let mut parts = claims.sub.splitn(3, ":");
+        let mut parts = self.sub.splitn(3, ":");
         let id_str = parts.next().ok_or_else(|| anyhow::anyhow!("no id"))?;
         let id: i32 = id_str.parse()?;
         let username = parts
             .next()
             .ok_or_else(|| anyhow::anyhow!("no name"))?
             .to_string();
-        let identity = Identity::from_str(parts.next().unwrap());
+        let identity =
+            Identity::from_str(parts.next().ok_or_else(|| anyhow::anyhow!("no identity"))?);
         // get principal info
         let principal = Principal {
             id,
@@ -133,9 +367,9 @@ impl JWT {
     }
 }
 
-/// Default for JwtAuth
+/// Default for JwtAuth, only for development and tests
 impl Default for JWT {
     fn default() -> Self {
-        Self::new(JwtConfig::default())
+        Self::new(&JwtConfig::default()).expect("the default jwt config is always valid")
     }
 }
@@ -1,4 +1,8 @@
 pub mod auth_layer;
+pub mod grpc_auth;
+pub mod guard;
 pub mod identity;
+pub mod jwks;
 pub mod jwt;
 pub mod principal;
+pub mod revocation;
@@ -7,6 +7,13 @@ pub struct Principal {
     pub identity: Identity,
 }
 
+impl Principal {
+    /// 判断当前用户的身份是否达到要求的最低等级
+    pub fn has_identity(&self, min: &Identity) -> bool {
+        self.identity >= *min
+    }
+}
+
 /// 手动实现 Debug trait
 impl std::fmt::Debug for Principal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
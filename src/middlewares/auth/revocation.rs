@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use mobc_redis::redis::AsyncCommands;
+
+use crate::{
+    db::get_global_redis_pool,
+    middlewares::auth::jwt::{Claims, current_timestamp_millis},
+};
+
+/// 被吊销 token 的 key 前缀，后面拼接 jti
+const DENYLIST_PREFIX: &str = "auth:denylist:";
+/// 用户会话吊销时间点（毫秒）的 key 前缀，后面拼接用户 id
+const REVOKED_BEFORE_PREFIX: &str = "auth:revoked_before_ms:";
+
+/// 吊销单个 token
+///
+/// # 功能描述
+/// 将 token 的 jti 写入 Redis 黑名单，过期时间与 token 的 exp 一致，token 过期后黑名单记录自动删除。
+///
+/// # 参数
+/// - `claims`: 要吊销的 token 的 Claims
+pub async fn revoke_token(claims: &Claims) -> anyhow::Result<()> {
+    let ttl = claims
+        .exp()
+        .saturating_sub(jsonwebtoken::get_current_timestamp());
+    // token 已经过期，无需加入黑名单
+    if ttl == 0 {
+        return Ok(());
+    }
+    let mut conn = get_global_redis_pool().get().await?;
+    let _: () = conn
+        .set_ex(format!("{DENYLIST_PREFIX}{}", claims.jti()), 1, ttl)
+        .await?;
+    Ok(())
+}
+
+/// 吊销用户的全部会话
+///
+/// # 功能描述
+/// 记录当前时间点（毫秒），在此之前签发给该用户的 access token 全部失效。
+/// 精确到毫秒，同一秒内先签发的 token 同样失效，吊销之后紧接着签发的新 token 不受影响。
+/// 记录只需保留一个 access token 的有效期，之后旧 token 会自然过期。
+///
+/// # 参数
+/// - `user_id`: 用户 id
+/// - `ttl`: access token 的有效期
+pub async fn revoke_user_sessions(user_id: i32, ttl: Duration) -> anyhow::Result<()> {
+    let mut conn = get_global_redis_pool().get().await?;
+    let _: () = conn
+        .set_ex(
+            format!("{REVOKED_BEFORE_PREFIX}{user_id}"),
+            current_timestamp_millis(),
+            ttl.as_secs().max(1),
+        )
+        .await?;
+    Ok(())
+}
+
+/// 判断 token 是否已被吊销
+///
+/// # 参数
+/// - `claims`: 要检查的 token 的 Claims
+/// - `user_id`: token 所属的用户 id
+///
+/// # 返回值
+/// token 在黑名单中，或签发时间早于用户会话吊销时间时返回 `true`
+pub async fn is_revoked(claims: &Claims, user_id: i32) -> anyhow::Result<bool> {
+    let mut conn = get_global_redis_pool().get().await?;
+    let (denied, revoked_before): (Option<u8>, Option<u64>) = mobc_redis::redis::pipe()
+        .get(format!("{DENYLIST_PREFIX}{}", claims.jti()))
+        .get(format!("{REVOKED_BEFORE_PREFIX}{user_id}"))
+        .query_async(&mut *conn)
+        .await?;
+    Ok(denied.is_some() || issued_before(claims, revoked_before))
+}
+
+/// 判断 token 是否签发于用户会话吊销之前
+///
+/// # 参数
+/// - `claims`: 要检查的 token 的 Claims
+/// - `revoked_before`: 用户会话的吊销时间点（毫秒），没有吊销时为 `None`
+pub fn issued_before(claims: &Claims, revoked_before: Option<u64>) -> bool {
+    revoked_before.is_some_and(|before| claims.iat_millis() < before)
+}
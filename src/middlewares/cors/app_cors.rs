@@ -1,4 +1,4 @@
-use crate::conf;
+use crate::{conf, middlewares::request_id::http_request_id::REQUEST_ID_HEADER};
 
 /// app 跨域中间件
 ///
@@ -14,7 +14,13 @@ pub fn app_cors() -> tower_http::cors::CorsLayer {
     // 创建跨域中间件
     tower_http::cors::CorsLayer::new()
         .allow_origin(origin_urls)
-        .allow_headers([axum::http::header::CONTENT_TYPE])
+        .allow_headers([
+            axum::http::header::CONTENT_TYPE,
+            axum::http::header::AUTHORIZATION,
+            REQUEST_ID_HEADER,
+        ])
+        // 允许前端读取请求 id，出错时可以提供给后端排查
+        .expose_headers([REQUEST_ID_HEADER])
         .allow_methods([
             axum::http::Method::GET,
             axum::http::Method::POST,
@@ -0,0 +1,131 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use tonic::Code;
+use tonic_health::pb::health_server::SERVICE_NAME as HEALTH_SERVICE_NAME;
+use tower::{Layer, Service};
+
+use crate::{
+    metrics::{GRPC_SERVER_HANDLED_TOTAL, GRPC_SERVER_HANDLING_SECONDS},
+    pb::user::user_service_server::SERVICE_NAME as USER_SERVICE_NAME,
+};
+
+/// 记录指标的 gRPC 方法，其他路径统一记为 `unknown`，避免任意路径撑大标签基数
+pub const KNOWN_GRPC_METHODS: &[(&str, &[&str])] = &[
+    (
+        USER_SERVICE_NAME,
+        &[
+            "UserLogin",
+            "UserRegister",
+            "UserExists",
+            "RefreshToken",
+            "UserLogout",
+            "RevokeUserSessions",
+            "GetUser",
+            "ListLoginHistory",
+            "ChangePassword",
+            "RequestPasswordReset",
+            "ConfirmPasswordReset",
+            "VerifyEmail",
+            "SendEmailVerification",
+            "ClearLoginLockout",
+            "ListUsers",
+            "SetUserStatus",
+            "SetUserLevel",
+        ],
+    ),
+    (HEALTH_SERVICE_NAME, &["Check", "Watch"]),
+];
+
+/// gRPC 指标层
+///
+/// # 功能描述
+/// 按服务和方法记录调用次数、耗时和 gRPC 状态码，不在 [`KNOWN_GRPC_METHODS`] 中的路径记为 `unknown`。状态码从响应头的 `grpc-status` 读取，
+/// 一元调用出错时 tonic 只返回头部，没有 `grpc-status` 说明调用成功；
+/// 流式调用的耗时只统计到返回响应头为止。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GrpcMetricsLayer;
+
+impl<S> Layer<S> for GrpcMetricsLayer {
+    type Service = GrpcMetrics<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcMetrics { inner }
+    }
+}
+
+/// gRPC 指标服务
+#[derive(Debug, Clone)]
+pub struct GrpcMetrics<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for GrpcMetrics<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        // 取出已经 ready 的 inner，留一个 clone 给下一次调用
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let (service, method) = split_grpc_path(request.uri().path());
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = inner.call(request).await;
+            let code = match &result {
+                Ok(response) => response_code(response.headers()),
+                Err(_) => Code::Unknown,
+            };
+            let labels = [
+                ("grpc_service", service.to_string()),
+                ("grpc_method", method.to_string()),
+                ("grpc_code", format!("{code:?}")),
+            ];
+            metrics::counter!(GRPC_SERVER_HANDLED_TOTAL, &labels).increment(1);
+            metrics::histogram!(GRPC_SERVER_HANDLING_SECONDS, &labels)
+                .record(start.elapsed().as_secs_f64());
+            result
+        })
+    }
+}
+
+/// 把 `/user.UserService/UserLogin` 拆分为服务名和方法名
+///
+/// # 返回值
+/// 只有 [`KNOWN_GRPC_METHODS`] 中的方法返回真实的服务名和方法名，其他路径返回 `unknown`
+pub fn split_grpc_path(path: &str) -> (&'static str, &'static str) {
+    path.trim_start_matches('/')
+        .split_once('/')
+        .and_then(|(service, method)| {
+            let (service, methods) = KNOWN_GRPC_METHODS
+                .iter()
+                .find(|(known, _)| *known == service)?;
+            let method = methods.iter().find(|known| **known == method)?;
+            Some((*service, *method))
+        })
+        .unwrap_or(("unknown", "unknown"))
+}
+
+/// 从响应头读取 gRPC 状态码，没有 `grpc-status` 时视为成功
+pub fn response_code(headers: &http::HeaderMap) -> Code {
+    headers
+        .get("grpc-status")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i32>().ok())
+        .map(Code::from_i32)
+        .unwrap_or(Code::Ok)
+}
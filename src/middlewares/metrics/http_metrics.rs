@@ -0,0 +1,47 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::metrics::{HTTP_REQUEST_DURATION_SECONDS, HTTP_REQUESTS_TOTAL};
+
+/// 没有匹配到路由时使用的 route 标签，避免把任意路径当作标签值
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// 记录 HTTP 请求数和耗时的中间件
+///
+/// # 功能描述
+/// route 标签使用路由模板（如 `/api/v1/admin/users/{id}`）而不是实际路径，
+/// 没有匹配到路由的请求统一记为 `unmatched`。
+///
+/// # 示例
+/// ```
+/// use axum::{Router, middleware, routing::get};
+/// use user_server::middlewares::metrics::http_metrics::track_http_metrics;
+///
+/// let router: Router = Router::new()
+///     .route("/users/{id}", get(|| async { "ok" }))
+///     .layer(middleware::from_fn(track_http_metrics));
+/// ```
+pub async fn track_http_metrics(request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let response = next.run(request).await;
+    let labels = [
+        ("method", method),
+        ("route", route),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!(HTTP_REQUESTS_TOTAL, &labels).increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION_SECONDS, &labels)
+        .record(start.elapsed().as_secs_f64());
+    response
+}
@@ -0,0 +1,2 @@
+pub mod grpc_metrics;
+pub mod http_metrics;
@@ -1,2 +1,4 @@
 pub mod auth;
 pub mod cors;
+pub mod metrics;
+pub mod request_id;
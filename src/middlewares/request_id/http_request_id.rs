@@ -0,0 +1,70 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+/// 请求 id 的请求头和 gRPC metadata 名称
+pub const REQUEST_ID_KEY: &str = "x-request-id";
+/// 请求 id 的请求头
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static(REQUEST_ID_KEY);
+/// 客户端传入的请求 id 的最大长度
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+tokio::task_local! {
+    /// 当前 HTTP 请求的 id，只在 `set_request_id` 处理的请求中可用
+    static REQUEST_ID: String;
+}
+
+/// 当前 HTTP 请求的 id，不在请求中时返回 `None`
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// 检查调用方传入的请求 id
+///
+/// # 功能描述
+/// 只接受不超过 64 个字符、由字母数字和 `-_.:` 组成的 id，避免把任意内容写入日志。
+///
+/// # 返回值
+/// 合法时返回去掉首尾空白的 id，否则返回 `None`
+pub fn parse_request_id(value: &str) -> Option<&str> {
+    let value = value.trim();
+    let valid = !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+    valid.then_some(value)
+}
+
+/// 读取请求头中的请求 id，没有或不合法时生成一个新的
+pub fn request_id_from_headers(headers: &http::HeaderMap) -> String {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_request_id)
+        .map(String::from)
+        .unwrap_or_else(|| xid::new().to_string())
+}
+
+/// 设置请求 id 的中间件
+///
+/// # 功能描述
+/// 使用客户端传入的 `x-request-id`，没有时生成一个 xid，并覆盖到请求头中，后续的日志 span 从请求头读取。
+/// 处理请求期间可以通过 `current_request_id` 取得，调用 gRPC 服务时会放入 metadata，
+/// 错误返回的 body 中也会带上。响应头同样返回 `x-request-id`。
+pub async fn set_request_id(mut request: Request, next: Next) -> Response {
+    let request_id = request_id_from_headers(request.headers());
+    // 合法的 id 和 xid 都是可见的 ASCII 字符
+    let header_value = HeaderValue::from_str(&request_id).expect("invalid request id");
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, header_value.clone());
+    let mut response = REQUEST_ID.scope(request_id, next.run(request)).await;
+    response
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, header_value);
+    response
+}
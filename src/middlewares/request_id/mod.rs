@@ -0,0 +1 @@
+pub mod http_request_id;
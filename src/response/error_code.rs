@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use tonic::{Code, Status};
+use tonic_types::{ErrorDetails, StatusExt};
+
+/// gRPC 错误详情 `ErrorInfo` 中的 domain，只有这个 domain 的 reason 才会当作错误码
+pub const ERROR_DOMAIN: &str = "user-server";
+
+/// 对外返回的错误码
+///
+/// # 功能描述
+/// 错误码序列化为 `USER_ALREADY_EXISTS` 这样的字符串，前端按错误码而不是提示信息判断错误类型。
+/// gRPC 服务通过 `ErrorInfo` 详情携带错误码，HTTP 网关转换时读取；没有携带时按 gRPC 状态码推断。
+/// 已经发布的错误码不能修改或删除。字符串形式和解析由 strum 按变体名生成，与 serde 使用同样的命名规则。
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::Display,
+    strum::EnumString,
+    strum::IntoStaticStr,
+    strum::VariantArray,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// 请求格式错误，如 JSON 无法解析、缺少路径参数
+    InvalidRequest,
+    /// 参数校验失败，字段级别的错误放在 data 中
+    ValidationFailed,
+    /// 不满足操作的前提条件，如邮箱已经验证过
+    PreconditionFailed,
+    /// 业务处理失败
+    BusinessError,
+    /// 令牌或链接无效、已过期
+    InvalidToken,
+    /// 尚未登录或登录已过期
+    Unauthenticated,
+    /// 帐号或密码不正确
+    InvalidCredentials,
+    /// 登录状态已失效，需要重新登录
+    SessionExpired,
+    /// 权限不足
+    PermissionDenied,
+    /// 帐号已被禁用
+    AccountDisabled,
+    /// 资源不存在
+    NotFound,
+    /// 请求方法不支持
+    MethodNotAllowed,
+    /// 资源已存在
+    AlreadyExists,
+    /// 用户名已被注册
+    UserAlreadyExists,
+    /// 邮箱已被注册
+    EmailAlreadyExists,
+    /// 并发修改冲突，可以重试
+    Conflict,
+    /// 请求过于频繁
+    TooManyRequests,
+    /// 服务端错误
+    InternalError,
+    /// 功能未实现
+    NotImplemented,
+    /// 服务暂时不可用
+    ServiceUnavailable,
+    /// 处理超时
+    Timeout,
+}
+
+impl ErrorCode {
+    /// 全部错误码
+    pub const ALL: &'static [ErrorCode] = <ErrorCode as strum::VariantArray>::VARIANTS;
+
+    /// 错误码的字符串形式，与序列化的结果一致
+    pub fn as_str(&self) -> &'static str {
+        self.into()
+    }
+
+    /// 解析错误码，未知的错误码返回 `None`
+    pub fn parse(value: &str) -> Option<ErrorCode> {
+        value.parse().ok()
+    }
+
+    /// 错误码对应的 HTTP 状态码
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidRequest
+            | ErrorCode::ValidationFailed
+            | ErrorCode::PreconditionFailed
+            | ErrorCode::BusinessError
+            | ErrorCode::InvalidToken => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthenticated
+            | ErrorCode::InvalidCredentials
+            | ErrorCode::SessionExpired => StatusCode::UNAUTHORIZED,
+            ErrorCode::PermissionDenied | ErrorCode::AccountDisabled => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::AlreadyExists
+            | ErrorCode::UserAlreadyExists
+            | ErrorCode::EmailAlreadyExists
+            | ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::NotImplemented => StatusCode::NOT_IMPLEMENTED,
+            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Timeout => StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    /// 按 gRPC 状态码推断错误码
+    pub fn from_grpc_code(code: Code) -> ErrorCode {
+        match code {
+            Code::InvalidArgument | Code::OutOfRange => ErrorCode::ValidationFailed,
+            Code::FailedPrecondition => ErrorCode::PreconditionFailed,
+            Code::Unauthenticated => ErrorCode::Unauthenticated,
+            Code::PermissionDenied => ErrorCode::PermissionDenied,
+            Code::NotFound => ErrorCode::NotFound,
+            Code::AlreadyExists => ErrorCode::AlreadyExists,
+            Code::Aborted => ErrorCode::Conflict,
+            Code::ResourceExhausted => ErrorCode::TooManyRequests,
+            Code::Unimplemented => ErrorCode::NotImplemented,
+            Code::Unavailable => ErrorCode::ServiceUnavailable,
+            Code::Cancelled | Code::DeadlineExceeded => ErrorCode::Timeout,
+            _ => ErrorCode::InternalError,
+        }
+    }
+
+    /// 读取 gRPC 错误携带的错误码
+    ///
+    /// # 功能描述
+    /// 优先使用 `ErrorInfo` 详情中本服务 domain 的 reason，没有或无法识别时按 gRPC 状态码推断。
+    pub fn from_status(status: &Status) -> ErrorCode {
+        status
+            .get_details_error_info()
+            .filter(|info| info.domain == ERROR_DOMAIN)
+            .and_then(|info| ErrorCode::parse(&info.reason))
+            .unwrap_or_else(|| ErrorCode::from_grpc_code(status.code()))
+    }
+
+    /// 生成携带错误码的 gRPC 错误
+    ///
+    /// # 参数
+    /// - `code`: gRPC 状态码
+    /// - `message`: 错误信息
+    ///
+    /// # 示例
+    /// ```
+    /// use tonic::Code;
+    /// use user_server::response::error_code::ErrorCode;
+    ///
+    /// let status = ErrorCode::InvalidCredentials.status(Code::Unauthenticated, "帐号或密码不正确！");
+    /// assert_eq!(ErrorCode::from_status(&status), ErrorCode::InvalidCredentials);
+    /// ```
+    pub fn status(self, code: Code, message: impl Into<String>) -> Status {
+        Status::with_error_details(
+            code,
+            message,
+            ErrorDetails::with_error_info(self.as_str(), ERROR_DOMAIN, HashMap::new()),
+        )
+    }
+}
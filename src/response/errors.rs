@@ -3,8 +3,12 @@ use axum::{
     response::IntoResponse,
 };
 use tonic::{Code, Status};
+use tonic_types::StatusExt;
 
-use crate::response::resp::ApiResponse;
+use crate::{
+    middlewares::request_id::http_request_id::current_request_id,
+    response::{error_code::ErrorCode, resp::ApiResponse},
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -20,8 +24,20 @@ pub enum ApiError {
     InternalServerError,
     #[error("参数校验失败：{0}")]
     ValidationError(String),
+    #[error("参数校验失败：{message}")]
+    InvalidFields {
+        message: String,
+        fields: Vec<FieldError>,
+    },
     #[error("尚未授权：{0}")]
     Unauthenticated(String),
+    #[error("权限不足：{0}")]
+    Forbidden(String),
+    #[error("请求过于频繁：{message}")]
+    TooManyRequests {
+        message: String,
+        retry_after: Option<u64>,
+    },
     #[error("无效的 JSON 数据: {0}")]
     InvalidJson(#[from] serde_json::Error),
     #[error("查询参数错误: {0}")]
@@ -34,27 +50,81 @@ pub enum ApiError {
     Argon2HashingError(#[from] argon2::password_hash::Error),
     #[error("密码加密时出错：{0}")]
     Argon2HashingPHCError(#[from] argon2::password_hash::phc::Error),
-    #[error("gRPC 调用错误：{0}")]
+    #[error("密码加密时出错：{0}")]
+    Argon2Error(#[from] argon2::Error),
+    #[error("密码校验时出错：{0}")]
+    BcryptError(#[from] bcrypt::BcryptError),
+    #[error("{}", .0.message())]
     GrpcError(Status), // 移除了 #[from] 属性，因为我们要自定义转换
 }
 
+/// 字段级别的校验错误
+///
+/// # 成员
+/// - field: 字段名
+/// - reason: 错误标识，如 `PASSWORD_TOO_SHORT`
+/// - message: 错误描述
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
+    pub message: String,
+}
+
 impl ApiError {
-    pub fn status_code(&self) -> axum::http::StatusCode {
+    /// 对外返回的错误码
+    pub fn error_code(&self) -> ErrorCode {
         match self {
-            ApiError::Biz(_) => axum::http::StatusCode::OK,
-            ApiError::NotFound => axum::http::StatusCode::NOT_FOUND,
-            ApiError::MethodNotAllowed => axum::http::StatusCode::METHOD_NOT_ALLOWED,
-            ApiError::Unauthenticated(_) => axum::http::StatusCode::UNAUTHORIZED,
-            ApiError::InternalServerError
-            | ApiError::ValidationError(_)
-            | ApiError::QueryError(_)
+            ApiError::Biz(_) => ErrorCode::BusinessError,
+            ApiError::NotFound => ErrorCode::NotFound,
+            ApiError::MethodNotAllowed => ErrorCode::MethodNotAllowed,
+            ApiError::Unauthenticated(_) => ErrorCode::Unauthenticated,
+            ApiError::Forbidden(_) => ErrorCode::PermissionDenied,
+            ApiError::TooManyRequests { .. } => ErrorCode::TooManyRequests,
+            ApiError::ValidationError(_) | ApiError::InvalidFields { .. } => {
+                ErrorCode::ValidationFailed
+            }
+            ApiError::QueryError(_)
             | ApiError::PathError(_)
             | ApiError::JsonError(_)
-            | ApiError::InvalidJson(_) => axum::http::StatusCode::BAD_REQUEST,
+            | ApiError::InvalidJson(_) => ErrorCode::InvalidRequest,
+            ApiError::InternalServerError
+            | ApiError::DatabaseError(_)
+            | ApiError::Argon2HashingError(_)
+            | ApiError::Argon2HashingPHCError(_)
+            | ApiError::Argon2Error(_)
+            | ApiError::BcryptError(_) => ErrorCode::InternalError,
+            ApiError::GrpcError(status) => ErrorCode::from_status(status),
+        }
+    }
+
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        match self {
+            // 请求格式错误时沿用 axum 给出的状态码，如 415、422
+            ApiError::QueryError(rejection) => rejection.status(),
+            ApiError::PathError(rejection) => rejection.status(),
+            ApiError::JsonError(rejection) => rejection.status(),
+            _ => self.error_code().http_status(),
+        }
+    }
+
+    /// 返回给客户端的提示信息，服务端错误不返回内部细节
+    fn public_message(&self) -> String {
+        match self {
+            ApiError::GrpcError(status)
+                if matches!(
+                    status.code(),
+                    Code::Internal | Code::Unknown | Code::DataLoss
+                ) =>
+            {
+                ApiError::InternalServerError.to_string()
+            }
             ApiError::DatabaseError(_)
             | ApiError::Argon2HashingError(_)
             | ApiError::Argon2HashingPHCError(_)
-            | ApiError::GrpcError(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            | ApiError::Argon2Error(_)
+            | ApiError::BcryptError(_) => ApiError::InternalServerError.to_string(),
+            _ => self.to_string(),
         }
     }
 }
@@ -62,11 +132,44 @@ impl ApiError {
 /// 将错误转换为响应
 impl IntoResponse for ApiError {
     fn into_response(self) -> axum::response::Response {
-        (
-            self.status_code(),
-            axum::Json(ApiResponse::<()>::err(self.to_string())),
-        )
-            .into_response()
+        if self.status_code().is_server_error() {
+            tracing::error!("request failed: {:?}", self);
+        }
+        let message = self.public_message();
+        let error = self.error_code();
+        let request_id = current_request_id();
+        let mut response = match &self {
+            // 字段级别的错误放在 data 中返回
+            ApiError::InvalidFields { fields, .. } => (
+                self.status_code(),
+                axum::Json(
+                    ApiResponse::new(-1, message, Some(fields))
+                        .with_error(error)
+                        .with_request_id(request_id),
+                ),
+            )
+                .into_response(),
+            _ => (
+                self.status_code(),
+                axum::Json(
+                    ApiResponse::<()>::err(message)
+                        .with_error(error)
+                        .with_request_id(request_id),
+                ),
+            )
+                .into_response(),
+        };
+        // 告诉客户端多久之后可以重试
+        if let ApiError::TooManyRequests {
+            retry_after: Some(retry_after),
+            ..
+        } = self
+        {
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, retry_after.into());
+        }
+        response
     }
 }
 
@@ -77,12 +180,32 @@ impl From<ApiError> for axum::http::Response<axum::body::Body> {
     }
 }
 
-/// 为 ApiError 实现转换为校验失败的 trait
+/// 为 ApiError 实现转换为校验失败的 trait，保留每个字段的错误
 impl From<axum_valid::ValidRejection<ApiError>> for ApiError {
     fn from(value: axum_valid::ValidRejection<ApiError>) -> Self {
         match value {
             axum_valid::ValidationRejection::Valid(errors) => {
-                ApiError::ValidationError(errors.to_string())
+                let mut fields = errors
+                    .field_errors()
+                    .into_iter()
+                    .flat_map(|(field, errors)| {
+                        errors.iter().map(move |error| FieldError {
+                            field: field.to_string(),
+                            reason: error.code.to_uppercase(),
+                            message: error.message.as_deref().unwrap_or(&error.code).to_string(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                fields.sort_by(|a, b| a.field.cmp(&b.field));
+                if fields.is_empty() {
+                    return ApiError::ValidationError(errors.to_string());
+                }
+                let message = fields
+                    .iter()
+                    .map(|field| field.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("；");
+                ApiError::InvalidFields { message, fields }
             }
             axum_valid::ValidationRejection::Inner(error) => error,
         }
@@ -90,21 +213,38 @@ impl From<axum_valid::ValidRejection<ApiError>> for ApiError {
 }
 
 /// 从 tonic::Status 转换为 ApiError
+///
+/// 错误码和 HTTP 状态码由 `ErrorCode::from_status` 决定，这里只拆出需要特殊处理的错误
 impl From<Status> for ApiError {
     fn from(value: Status) -> Self {
-        // 根据 Status 的 code 映射到不同的 ApiError
         match value.code() {
-            Code::NotFound => ApiError::NotFound,
-            Code::InvalidArgument => ApiError::ValidationError(value.message().to_string()),
-            Code::Unauthenticated => ApiError::Unauthenticated(value.message().to_string()),
-            Code::PermissionDenied => ApiError::Unauthenticated(value.message().to_string()),
-            Code::AlreadyExists => ApiError::ValidationError(value.message().to_string()),
-            Code::FailedPrecondition => ApiError::ValidationError(value.message().to_string()),
-            Code::OutOfRange => ApiError::ValidationError(value.message().to_string()),
-            Code::Unimplemented => ApiError::MethodNotAllowed,
-
-            // 其他错误都视为内部服务器错误
-            _ => ApiError::InternalServerError,
+            Code::InvalidArgument => match value.get_details_bad_request() {
+                // 携带 BadRequest 详情时保留每个字段的错误
+                Some(bad_request) if !bad_request.field_violations.is_empty() => {
+                    ApiError::InvalidFields {
+                        message: value.message().to_string(),
+                        fields: bad_request
+                            .field_violations
+                            .into_iter()
+                            .map(|violation| FieldError {
+                                field: violation.field,
+                                reason: violation.reason,
+                                message: violation.description,
+                            })
+                            .collect(),
+                    }
+                }
+                _ => ApiError::GrpcError(value),
+            },
+            Code::ResourceExhausted => ApiError::TooManyRequests {
+                message: value.message().to_string(),
+                retry_after: value
+                    .metadata()
+                    .get("retry-after")
+                    .and_then(|retry_after| retry_after.to_str().ok())
+                    .and_then(|retry_after| retry_after.parse().ok()),
+            },
+            _ => ApiError::GrpcError(value),
         }
     }
 }
@@ -1,3 +1,4 @@
+pub mod error_code;
 pub mod errors;
 pub mod resp;
 
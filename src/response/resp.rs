@@ -1,13 +1,21 @@
+use crate::response::error_code::ErrorCode;
+
 /// 自定义 ApiResponse 封装
 ///
 /// # 成员
 /// - code：状态码
+/// - error：错误码，只在出错时返回，如 `USER_ALREADY_EXISTS`
 /// - message：返回的信息
+/// - request_id：请求 id，只在出错时返回，与响应头的 `x-request-id` 相同，便于排查问题
 /// - data：返回的数据，可选。
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct ApiResponse<T> {
     pub code: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorCode>,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")] // 如果是空的就跳过序列化
     pub data: Option<T>,
 }
@@ -16,10 +24,22 @@ impl<T: serde::Serialize> ApiResponse<T> {
     pub fn new(code: i32, message: String, data: Option<T>) -> ApiResponse<T> {
         Self {
             code,
+            error: None,
             message,
+            request_id: None,
             data,
         }
     }
+    /// 设置错误码
+    pub fn with_error(mut self, error: ErrorCode) -> Self {
+        self.error = Some(error);
+        self
+    }
+    /// 设置请求 id
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
     /// 成功返回，自定义消息和数据 200,custom msg,Data
     pub fn ok<M: AsRef<str>>(message: M, data: Option<T>) -> Self {
         Self::new(200, String::from(message.as_ref()), data)
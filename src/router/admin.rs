@@ -0,0 +1,39 @@
+use crate::{
+    handlers,
+    middlewares::auth::{
+        auth_layer::get_auth_layer,
+        guard::{level, require_identity},
+    },
+    state::app_state::AppState,
+};
+
+/// 创建管理员相关的路由，所有路由都需要管理员身份才能访问
+pub fn create_admin_router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/lockouts/{username}",
+            axum::routing::delete(handlers::admin::lockout::clear_login_lockout_handler),
+        )
+        .route(
+            "/users",
+            axum::routing::get(handlers::admin::users::list_users_handler),
+        )
+        .route(
+            "/users/{user_id}",
+            axum::routing::get(handlers::admin::users::get_user_handler),
+        )
+        .route(
+            "/users/{user_id}/status",
+            axum::routing::put(handlers::admin::users::set_user_status_handler),
+        )
+        .route(
+            "/users/{user_id}/level",
+            axum::routing::put(handlers::admin::users::set_user_level_handler),
+        )
+        .route(
+            "/users/{user_id}/logins",
+            axum::routing::get(handlers::admin::login_history::list_user_logins_handler),
+        )
+        .route_layer(axum::middleware::from_fn(require_identity::<level::Admin>))
+        .route_layer(get_auth_layer().clone())
+}
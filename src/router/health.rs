@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode};
+use tonic_health::pb::{HealthCheckRequest, health_check_response::ServingStatus};
+
+use crate::{
+    db::{get_global_redis_pool, redis},
+    pb::user::user_service_server::SERVICE_NAME,
+    response::resp::ApiResponse,
+    state::app_state::AppState,
+};
+
+/// 单个依赖检查的超时时间
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 健康检查路由，挂载在根路径下，不需要登录
+///
+/// - `/health`: 存活检查，进程能处理请求就返回 200
+/// - `/ready`: 就绪检查，gRPC 服务和 Redis 都可用时返回 200，否则返回 503
+pub fn get_health_router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route("/health", axum::routing::get(liveness_handler))
+        .route("/ready", axum::routing::get(readiness_handler))
+}
+
+/// 依赖的检查结果
+///
+/// # 成员
+/// - grpc: gRPC 服务的状态，`UP` 或 `DOWN`
+/// - redis: Redis 的状态，`UP` 或 `DOWN`
+#[derive(Debug, serde::Serialize)]
+pub struct ReadinessChecks {
+    pub grpc: &'static str,
+    pub redis: &'static str,
+}
+
+/// 存活检查
+pub async fn liveness_handler() -> ApiResponse<()> {
+    ApiResponse::success_with_msg("UP")
+}
+
+/// 就绪检查
+///
+/// # 功能描述
+/// 并发检查 gRPC 服务的 `grpc.health.v1.Health` 状态和 Redis 的 `PING`，
+/// 任意一个不可用时返回 503，data 中给出每个依赖的状态。
+pub async fn readiness_handler(
+    State(AppState { grpc_factory, .. }): State<AppState>,
+) -> (StatusCode, ApiResponse<ReadinessChecks>) {
+    let grpc = async {
+        let mut client = grpc_factory.create_health_client();
+        let request = HealthCheckRequest {
+            service: SERVICE_NAME.to_string(),
+        };
+        match tokio::time::timeout(CHECK_TIMEOUT, client.check(request)).await {
+            Ok(Ok(response)) => response.into_inner().status() == ServingStatus::Serving,
+            Ok(Err(status)) => {
+                tracing::warn!("gRPC 服务健康检查失败: {:?}", status);
+                false
+            }
+            Err(_) => {
+                tracing::warn!("gRPC 服务健康检查超时");
+                false
+            }
+        }
+    };
+    let redis = async {
+        redis::ping(get_global_redis_pool(), CHECK_TIMEOUT)
+            .await
+            .inspect_err(|e| tracing::warn!("Redis 健康检查失败: {:?}", e))
+            .is_ok()
+    };
+    let (grpc, redis) = tokio::join!(grpc, redis);
+    let state = |up: bool| if up { "UP" } else { "DOWN" };
+    let checks = ReadinessChecks {
+        grpc: state(grpc),
+        redis: state(redis),
+    };
+    if grpc && redis {
+        (StatusCode::OK, ApiResponse::ok("UP", Some(checks)))
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            ApiResponse::new(-1, "DOWN".to_string(), Some(checks)),
+        )
+    }
+}
@@ -0,0 +1,21 @@
+use axum::Json;
+use jsonwebtoken::jwk::JwkSet;
+
+use crate::{middlewares::auth::jwt::get_global_jwt, state::app_state::AppState};
+
+/// 公布用于校验 token 的公钥，挂载在根路径下，不需要登录
+pub fn get_jwks_router() -> axum::Router<AppState> {
+    axum::Router::new().route(
+        "/.well-known/jwks.json",
+        axum::routing::get(get_jwks_handler),
+    )
+}
+
+/// 返回 JWK Set
+///
+/// # 功能描述
+/// 按照 RFC 7517 的格式直接返回 JWK Set，不使用 `ApiResponse` 包装，
+/// 以便其他服务和标准的 JWT 库直接使用。HMAC 算法下返回空的密钥集合。
+pub async fn get_jwks_handler() -> Json<JwkSet> {
+    Json(get_global_jwt().jwks())
+}
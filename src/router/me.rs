@@ -0,0 +1,23 @@
+use crate::{handlers, middlewares::auth::auth_layer::get_auth_layer, state::app_state::AppState};
+
+/// 创建当前登录用户相关的路由，所有路由都需要登录后才能访问
+pub fn create_me_router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/",
+            axum::routing::get(handlers::me::profile::get_profile_handler),
+        )
+        .route(
+            "/password",
+            axum::routing::put(handlers::me::password::change_password_handler),
+        )
+        .route(
+            "/email/verification",
+            axum::routing::post(handlers::me::email::send_email_verification_handler),
+        )
+        .route(
+            "/logins",
+            axum::routing::get(handlers::me::logins::list_my_logins_handler),
+        )
+        .route_layer(get_auth_layer().clone())
+}
@@ -0,0 +1,45 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+/// Prometheus 指标路由，挂载在根路径下，不需要登录，只能挂在单独的内部端口上
+///
+/// - `/metrics`: 以 Prometheus 文本格式输出指标
+pub fn get_metrics_router<S>() -> axum::Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    axum::Router::new().route("/metrics", axum::routing::get(metrics_handler))
+}
+
+/// 输出指标，没有开启指标时返回 404
+pub async fn metrics_handler() -> Response {
+    match crate::metrics::render() {
+        Some(body) => ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// 单独监听一个端口暴露 /metrics
+///
+/// # 功能描述
+/// 指标中包含各接口的流量、登录失败次数和连接池状态，不能挂在对外的业务端口上，
+/// 绑定端口后在后台运行，服务异常退出时只记录日志。
+///
+/// # 参数
+/// - `port`: 监听的端口
+pub async fn serve_metrics(port: u16) -> anyhow::Result<()> {
+    let addr = format!("0.0.0.0:{port}");
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    tracing::info!(
+        "Serving metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, get_metrics_router::<()>()).await {
+            tracing::error!("指标服务异常退出: {:?}", e);
+        }
+    });
+    Ok(())
+}
@@ -3,6 +3,11 @@ use crate::{
     state::app_state::AppState,
 };
 
+pub mod admin;
+pub mod health;
+pub mod jwks;
+pub mod me;
+pub mod metrics;
 pub mod user;
 pub mod version;
 
@@ -11,6 +16,8 @@ pub fn merge_router() -> axum::Router<AppState> {
     axum::Router::new()
         .nest("/get/current", version::get_version_router())
         .nest("/user", user::create_user_router())
+        .nest("/me", me::create_me_router())
+        .nest("/admin", admin::create_admin_router())
         .fallback(async || -> ApiResult<()> {
             // 路径找不到
             tracing::warn!("Not Found");
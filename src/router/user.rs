@@ -1,7 +1,19 @@
-use crate::{handlers, state::app_state::AppState};
+use crate::{handlers, middlewares::auth::auth_layer::get_auth_layer, state::app_state::AppState};
 
 /// 创建解析相关的路由，专门用来管理与原文解析相关的操作
 pub fn create_user_router() -> axum::Router<AppState> {
+    // 需要登录才能访问的路由
+    let protected_router = axum::Router::new()
+        .route(
+            "/logout",
+            axum::routing::post(handlers::user::logout::user_logout_handler),
+        )
+        .route(
+            "/logout/all",
+            axum::routing::post(handlers::user::logout::revoke_user_sessions_handler),
+        )
+        .route_layer(get_auth_layer().clone());
+
     axum::Router::new()
         .route(
             "/register",
@@ -11,4 +23,21 @@ pub fn create_user_router() -> axum::Router<AppState> {
             "/login",
             axum::routing::post(handlers::user::login::user_login_handler),
         )
+        .route(
+            "/refresh",
+            axum::routing::post(handlers::user::refresh::refresh_token_handler),
+        )
+        .route(
+            "/email/verify",
+            axum::routing::post(handlers::user::email::verify_email_handler),
+        )
+        .route(
+            "/password/reset",
+            axum::routing::post(handlers::user::password_reset::request_password_reset_handler),
+        )
+        .route(
+            "/password/reset/confirm",
+            axum::routing::post(handlers::user::password_reset::confirm_password_reset_handler),
+        )
+        .merge(protected_router)
 }
@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use sqlx::{
+    PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use tonic::{Code, Status};
+use tracing::Instrument;
+
+use crate::{
+    conf::mail::MailConfig,
+    db::{error::db_error, pgsql, trace::db_span},
+    mail::{Mail, Mailer},
+    response::error_code::ErrorCode,
+    utils::crypto::{generate_token, hash_token},
+};
+
+/// 数据库错误日志中的操作说明
+const DB_CONTEXT: &str = "邮箱验证令牌数据库操作失败";
+
+/// email_verification_token 表中的一条记录
+#[derive(Debug, sqlx::FromRow)]
+struct EmailVerificationRecord {
+    user_id: i32,
+    email: String,
+    expires_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+}
+
+/// 签发邮箱验证令牌
+///
+/// # 功能描述
+/// 生成随机令牌，数据库中只保存它的 SHA-256 摘要和待验证的邮箱。
+/// 同一个用户之前未使用的令牌全部作废，只有最新的一封邮件有效。
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `user_id`: 用户 id
+/// - `email`: 待验证的邮箱
+/// - `ttl`: 令牌有效期
+pub async fn issue(
+    pool: &PgPool,
+    user_id: i32,
+    email: &str,
+    ttl: Duration,
+) -> Result<String, Status> {
+    let token = generate_token();
+    let mut tx = pgsql::begin(pool).await?;
+    sqlx::query(
+        r#"UPDATE email_verification_token SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL"#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .instrument(db_span("UPDATE", "email_verification_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    sqlx::query(
+        r#"INSERT INTO email_verification_token (user_id, email, token_hash, expires_at) VALUES ($1, $2, $3, NOW() + make_interval(secs => $4))"#,
+    )
+    .bind(user_id)
+    .bind(email)
+    .bind(hash_token(&token))
+    .bind(ttl.as_secs_f64())
+    .execute(&mut *tx)
+    .instrument(db_span("INSERT", "email_verification_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    tx.commit().await.map_err(db_error(DB_CONTEXT))?;
+    Ok(token)
+}
+
+/// 使用邮箱验证令牌
+///
+/// # 功能描述
+/// 锁定并校验令牌，令牌中的邮箱与用户当前的邮箱一致时标记邮箱已验证，
+/// 同时作废该用户全部未使用的令牌。令牌不存在、已使用、已过期或邮箱已变更时返回同样的错误。
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `token`: 邮件中的原始令牌
+///
+/// # 返回值
+/// 返回令牌所属的用户 id
+pub async fn verify(pool: &PgPool, token: &str) -> Result<i32, Status> {
+    let invalid = || {
+        ErrorCode::InvalidToken.status(
+            Code::InvalidArgument,
+            "验证链接无效或已过期，请重新发送验证邮件！",
+        )
+    };
+    let mut tx = pgsql::begin(pool).await?;
+    let record = sqlx::query_as::<_, EmailVerificationRecord>(
+        r#"SELECT user_id, email, expires_at, used_at FROM email_verification_token WHERE token_hash = $1 FOR UPDATE"#,
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&mut *tx)
+    .instrument(db_span("SELECT", "email_verification_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?
+    .ok_or_else(invalid)?;
+    if record.used_at.is_some() || record.expires_at <= Utc::now() {
+        return Err(invalid());
+    }
+    let verified = sqlx::query(
+        r#"UPDATE "user" SET email_verified_at = NOW() WHERE id = $1 AND LOWER(email) = LOWER($2)"#,
+    )
+    .bind(record.user_id)
+    .bind(&record.email)
+    .execute(&mut *tx)
+    .instrument(db_span("UPDATE", "user"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    if verified.rows_affected() == 0 {
+        return Err(invalid());
+    }
+    sqlx::query(
+        r#"UPDATE email_verification_token SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL"#,
+    )
+    .bind(record.user_id)
+    .execute(&mut *tx)
+    .instrument(db_span("UPDATE", "email_verification_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    tx.commit().await.map_err(db_error(DB_CONTEXT))?;
+    Ok(record.user_id)
+}
+
+/// 向用户发送验证邮件
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `mailer`: 邮件发送器
+/// - `config`: 邮件配置
+/// - `user_id`: 用户 id
+/// - `username`: 用户名，用于邮件称呼
+/// - `email`: 待验证的邮箱
+pub async fn send_verification_mail(
+    pool: &PgPool,
+    mailer: &dyn Mailer,
+    config: &MailConfig,
+    user_id: i32,
+    username: &str,
+    email: &str,
+) -> anyhow::Result<()> {
+    let ttl = Duration::from_secs(config.email_verification_ttl_secs());
+    let token = issue(pool, user_id, email, ttl).await?;
+    let url = config.email_verification_url().replace("{token}", &token);
+    mailer
+        .send(Mail {
+            to: email.to_string(),
+            subject: "验证邮箱".to_string(),
+            body: format!(
+                "{username}，你好：\n\n请在 {} 小时内打开下面的链接验证邮箱：\n{url}\n\n如果这不是你本人的操作，请忽略这封邮件。\n",
+                ttl.as_secs() / 3600
+            ),
+        })
+        .await
+}
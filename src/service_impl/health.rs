@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tonic_health::{ServingStatus, server::HealthReporter};
+
+use crate::{db::pgsql, pb::user::user_service_server::SERVICE_NAME};
+
+/// 数据库健康检查的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// 单次数据库健康检查的超时时间
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 按 Postgres 的连接状态持续更新 `grpc.health.v1.Health` 的服务状态
+///
+/// # 功能描述
+/// 每隔一段时间执行一次 `SELECT 1`，可用时把整体状态（空服务名）和 `user.UserService`
+/// 标记为 `SERVING`，不可用时标记为 `NOT_SERVING`。只在状态变化时记录日志。
+/// 应在服务启动时通过 `tokio::spawn` 在后台运行。
+///
+/// # 参数
+/// - `reporter`: 健康状态的上报句柄
+/// - `pool`: 数据库连接池
+pub async fn report_database_health(reporter: HealthReporter, pool: &'static PgPool) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    let mut last_status = ServingStatus::Unknown;
+    loop {
+        interval.tick().await;
+        let status = match pgsql::ping(pool, CHECK_TIMEOUT).await {
+            Ok(()) => ServingStatus::Serving,
+            Err(e) => {
+                if last_status != ServingStatus::NotServing {
+                    tracing::error!("数据库不可用，gRPC 健康状态改为 NOT_SERVING: {:?}", e);
+                }
+                ServingStatus::NotServing
+            }
+        };
+        if status == last_status {
+            continue;
+        }
+        if status == ServingStatus::Serving {
+            tracing::info!("数据库可用，gRPC 健康状态改为 SERVING");
+        }
+        reporter.set_service_status("", status).await;
+        reporter.set_service_status(SERVICE_NAME, status).await;
+        last_status = status;
+    }
+}
@@ -0,0 +1,127 @@
+use sqlx::{
+    PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use tonic::Status;
+use tracing::Instrument;
+
+use crate::{
+    common::client_info::ClientInfo,
+    db::{error::db_error, pgsql, trace::db_span},
+    metrics,
+    pb::user::LoginRecord,
+    utils::timezone::east8,
+};
+
+/// 登录结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginOutcome {
+    Success,
+    UnknownUser,
+    BadPassword,
+    Disabled,
+    Locked,
+}
+
+impl LoginOutcome {
+    /// 失败原因，登录成功时为空
+    pub fn reason(&self) -> Option<&'static str> {
+        match self {
+            LoginOutcome::Success => None,
+            LoginOutcome::UnknownUser => Some("unknown_user"),
+            LoginOutcome::BadPassword => Some("bad_password"),
+            LoginOutcome::Disabled => Some("disabled"),
+            LoginOutcome::Locked => Some("locked"),
+        }
+    }
+}
+
+/// login_history 表中的一条记录
+#[derive(Debug, sqlx::FromRow)]
+struct LoginHistoryRecord {
+    id: i64,
+    success: bool,
+    reason: Option<String>,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// 登录记录转换成 gRPC 响应，时间统一转换为东八区的 RFC 3339 格式
+impl From<LoginHistoryRecord> for LoginRecord {
+    fn from(value: LoginHistoryRecord) -> Self {
+        let created_at = match east8() {
+            Some(offset) => value.created_at.with_timezone(&offset).to_rfc3339(),
+            None => value.created_at.to_rfc3339(),
+        };
+        LoginRecord {
+            id: value.id,
+            success: value.success,
+            reason: value.reason,
+            ip: value.ip,
+            user_agent: value.user_agent,
+            created_at,
+        }
+    }
+}
+
+/// 记录一次登录尝试
+///
+/// # 功能描述
+/// 登录历史只用于审计，写入失败时只记录日志，不影响登录结果。
+/// IP 是解析过的地址（只信任可信代理转发的 IP），用户名和 User-Agent 按列宽截断，
+/// 调用方无法通过构造过长的值使写入失败、逃避审计。
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `user_id`: 用户 id，用户名不存在时为空
+/// - `username`: 登录时使用的用户名
+/// - `outcome`: 登录结果
+/// - `client`: 客户端信息
+pub async fn record(
+    pool: &PgPool,
+    user_id: Option<i32>,
+    username: &str,
+    outcome: LoginOutcome,
+    client: &ClientInfo,
+) {
+    metrics::record_login(outcome.reason().unwrap_or("success"));
+    // 获取连接失败时已经记录日志
+    let Ok(mut conn) = pgsql::acquire(pool).await else {
+        return;
+    };
+    let result = sqlx::query(
+        r#"INSERT INTO login_history (user_id, username, success, reason, ip, user_agent) VALUES ($1, $2, $3, $4, $5::INET, $6)"#,
+    )
+    .bind(user_id)
+    .bind(username.chars().take(50).collect::<String>())
+    .bind(outcome == LoginOutcome::Success)
+    .bind(outcome.reason())
+    .bind(client.ip.map(|ip| ip.to_string()))
+    .bind(&client.user_agent)
+    .execute(&mut *conn)
+    .instrument(db_span("INSERT", "login_history"))
+    .await;
+    if let Err(e) = result {
+        tracing::error!("记录登录历史失败: {:?}", e);
+    }
+}
+
+/// 查询用户最近的登录记录
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `user_id`: 用户 id
+/// - `limit`: 返回的记录数
+pub async fn list(pool: &PgPool, user_id: i32, limit: i64) -> Result<Vec<LoginRecord>, Status> {
+    let records = sqlx::query_as::<_, LoginHistoryRecord>(
+        r#"SELECT id, success, reason, host(ip) AS ip, user_agent, created_at FROM login_history WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2"#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(&mut *pgsql::acquire(pool).await?)
+    .instrument(db_span("SELECT", "login_history"))
+    .await
+    .map_err(db_error("查询登录历史失败"))?;
+    Ok(records.into_iter().map(Into::into).collect())
+}
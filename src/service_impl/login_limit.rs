@@ -0,0 +1,193 @@
+use mobc_redis::redis::AsyncCommands;
+use tonic::{Status, metadata::MetadataValue};
+
+use crate::{conf::login_limit::LoginLimitConfig, db::get_global_redis_pool};
+
+/// 登录失败次数的 key 前缀，后面拼接 `user:{username}` 或 `ip:{ip}`
+const FAILURE_PREFIX: &str = "auth:login_failures:";
+/// 登录锁定的 key 前缀
+const LOCKOUT_PREFIX: &str = "auth:login_lockout:";
+/// 锁定次数的 key 前缀，用于计算指数退避
+const LOCKOUT_COUNT_PREFIX: &str = "auth:login_lockout_count:";
+/// 锁定时返回给客户端的重试时间
+pub const RETRY_AFTER_METADATA: &str = "retry-after";
+
+/// 登录限流
+///
+/// # 功能描述
+/// 按用户名和客户端 IP 分别统计登录失败次数，达到阈值后锁定一段时间，
+/// 锁定时长随锁定次数指数增长，锁定期间的登录请求直接返回 `ResourceExhausted`。
+#[derive(Debug, Clone)]
+pub struct LoginLimiter {
+    config: LoginLimitConfig,
+}
+
+/// 限流的对象
+struct Subject {
+    key: String,
+    max_failures: u64,
+}
+
+impl LoginLimiter {
+    pub fn new(config: LoginLimitConfig) -> Self {
+        Self { config }
+    }
+
+    /// 检查用户名和 IP 是否处于锁定状态
+    ///
+    /// # 参数
+    /// - `username`: 登录的账号，见 [`login_subject`]
+    /// - `ip`: 客户端 IP
+    ///
+    /// # 返回值
+    /// 处于锁定状态时返回携带 `retry-after` 的 `ResourceExhausted`
+    pub async fn check(&self, username: &str, ip: Option<&str>) -> Result<(), Status> {
+        let mut conn = get_global_redis_pool().get().await.map_err(redis_error)?;
+        let mut pipe = mobc_redis::redis::pipe();
+        for subject in self.subjects(username, ip) {
+            pipe.ttl(format!("{LOCKOUT_PREFIX}{}", subject.key));
+        }
+        let ttls: Vec<i64> = pipe.query_async(&mut *conn).await.map_err(redis_error)?;
+        match ttls.into_iter().max() {
+            Some(retry_after) if retry_after > 0 => Err(locked(retry_after as u64)),
+            _ => Ok(()),
+        }
+    }
+
+    /// 记录一次登录失败
+    ///
+    /// # 功能描述
+    /// 用户名和 IP 的失败次数分别加一，达到阈值时清空失败次数并锁定，
+    /// 锁定时长为 `base_lockout_secs * 2^(锁定次数 - 1)`，不超过 `max_lockout_secs`。
+    ///
+    /// # 返回值
+    /// 本次失败触发锁定时返回携带 `retry-after` 的 `ResourceExhausted`
+    pub async fn record_failure(&self, username: &str, ip: Option<&str>) -> Result<(), Status> {
+        let mut conn = get_global_redis_pool().get().await.map_err(redis_error)?;
+        let mut retry_after = 0;
+        for subject in self.subjects(username, ip) {
+            let failure_key = format!("{FAILURE_PREFIX}{}", subject.key);
+            let (failures,): (u64,) = mobc_redis::redis::pipe()
+                .atomic()
+                .incr(&failure_key, 1)
+                .expire(&failure_key, self.config.failure_window_secs() as i64)
+                .ignore()
+                .query_async(&mut *conn)
+                .await
+                .map_err(redis_error)?;
+            if failures < subject.max_failures {
+                continue;
+            }
+            // 达到阈值，按锁定次数计算锁定时长
+            let count_key = format!("{LOCKOUT_COUNT_PREFIX}{}", subject.key);
+            let (count,): (u64,) = mobc_redis::redis::pipe()
+                .atomic()
+                .incr(&count_key, 1)
+                .expire(&count_key, self.config.lockout_reset_secs() as i64)
+                .ignore()
+                .query_async(&mut *conn)
+                .await
+                .map_err(redis_error)?;
+            let lockout_secs = self.config.lockout_secs(count);
+            let _: () = mobc_redis::redis::pipe()
+                .atomic()
+                .set_ex(
+                    format!("{LOCKOUT_PREFIX}{}", subject.key),
+                    1,
+                    lockout_secs.max(1),
+                )
+                .ignore()
+                .del(&failure_key)
+                .ignore()
+                .query_async(&mut *conn)
+                .await
+                .map_err(redis_error)?;
+            tracing::warn!("login locked for {}s: {}", lockout_secs, subject.key);
+            retry_after = retry_after.max(lockout_secs);
+        }
+        if retry_after > 0 {
+            return Err(locked(retry_after));
+        }
+        Ok(())
+    }
+
+    /// 登录成功后清空用户名的失败次数，IP 的失败次数保留到窗口结束
+    pub async fn record_success(&self, username: &str) -> Result<(), Status> {
+        let mut conn = get_global_redis_pool().get().await.map_err(redis_error)?;
+        let _: () = conn
+            .del(format!("{FAILURE_PREFIX}user:{username}"))
+            .await
+            .map_err(redis_error)?;
+        Ok(())
+    }
+
+    fn subjects(&self, username: &str, ip: Option<&str>) -> Vec<Subject> {
+        let mut subjects = vec![Subject {
+            key: format!("user:{username}"),
+            max_failures: self.config.max_user_failures(),
+        }];
+        if let Some(ip) = ip {
+            subjects.push(Subject {
+                key: format!("ip:{ip}"),
+                max_failures: self.config.max_ip_failures(),
+            });
+        }
+        subjects
+    }
+}
+
+/// 登录限流和登录历史使用的账号标识
+///
+/// # 功能描述
+/// 同一账号可以用用户名或邮箱（不区分大小写）登录，限流需要落在同一个账号上：
+/// 账号存在时使用账号的用户名，否则使用规范化后的输入，邮箱统一转为小写。
+///
+/// # 参数
+/// - `resolved`: 查询到的账号用户名
+/// - `input`: 客户端提交的用户名或邮箱
+pub fn login_subject(resolved: Option<&str>, input: &str) -> String {
+    match resolved {
+        Some(username) => username.to_string(),
+        None if input.contains('@') => input.trim().to_lowercase(),
+        None => input.trim().to_string(),
+    }
+}
+
+/// 解除锁定
+///
+/// # 功能描述
+/// 清空用户名（以及可选的 IP）的锁定状态、失败次数和锁定次数，供管理员使用。
+///
+/// # 参数
+/// - `username`: 用户名
+/// - `ip`: 客户端 IP
+pub async fn clear_lockout(username: &str, ip: Option<&str>) -> anyhow::Result<()> {
+    let mut keys = Vec::new();
+    let username = login_subject(None, username);
+    let subjects =
+        std::iter::once(format!("user:{username}")).chain(ip.map(|ip| format!("ip:{ip}")));
+    for subject in subjects {
+        keys.push(format!("{FAILURE_PREFIX}{subject}"));
+        keys.push(format!("{LOCKOUT_PREFIX}{subject}"));
+        keys.push(format!("{LOCKOUT_COUNT_PREFIX}{subject}"));
+    }
+    let mut conn = get_global_redis_pool().get().await?;
+    let _: () = conn.del(keys).await?;
+    Ok(())
+}
+
+/// 锁定状态，`retry-after` 为剩余的锁定时间（秒）
+fn locked(retry_after: u64) -> Status {
+    let mut status =
+        Status::resource_exhausted(format!("登录失败次数过多，请在 {retry_after} 秒后重试！"));
+    status
+        .metadata_mut()
+        .insert(RETRY_AFTER_METADATA, MetadataValue::from(retry_after));
+    status
+}
+
+/// Redis 错误统一记录日志，对外只返回内部错误
+fn redis_error(e: impl std::fmt::Debug) -> Status {
+    tracing::error!("登录限流 Redis 操作失败: {:?}", e);
+    Status::internal("服务器内部错误")
+}
@@ -1 +1,9 @@
+pub mod email_verification;
+pub mod health;
+pub mod login_history;
+pub mod login_limit;
+pub mod password_policy;
+pub mod password_reset;
+pub mod refresh_token;
 pub mod user;
+pub mod user_admin;
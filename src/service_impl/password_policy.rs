@@ -0,0 +1,283 @@
+use std::collections::HashSet;
+
+use prost::Message;
+use tonic::{Code, Status};
+use tonic_types::{BadRequest, pb};
+
+use crate::conf::password_policy::PasswordPolicyConfig;
+
+/// 用户名短于这个长度时不检查密码是否包含用户名
+const MIN_USERNAME_CHECK_LEN: usize = 3;
+
+/// 违反的密码规则
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyViolation {
+    TooShort { min: usize },
+    TooLong { max: usize },
+    MissingLowercase,
+    MissingUppercase,
+    MissingDigit,
+    MissingSymbol,
+    TooFewCharacterClasses { min: usize },
+    LowEntropy { min_bits: f64 },
+    ContainsUsername,
+    Blocklisted,
+}
+
+impl PolicyViolation {
+    /// 规则的错误标识，写入 gRPC 错误详情的 reason
+    pub fn reason(&self) -> &'static str {
+        match self {
+            PolicyViolation::TooShort { .. } => "PASSWORD_TOO_SHORT",
+            PolicyViolation::TooLong { .. } => "PASSWORD_TOO_LONG",
+            PolicyViolation::MissingLowercase => "PASSWORD_MISSING_LOWERCASE",
+            PolicyViolation::MissingUppercase => "PASSWORD_MISSING_UPPERCASE",
+            PolicyViolation::MissingDigit => "PASSWORD_MISSING_DIGIT",
+            PolicyViolation::MissingSymbol => "PASSWORD_MISSING_SYMBOL",
+            PolicyViolation::TooFewCharacterClasses { .. } => "PASSWORD_TOO_FEW_CHARACTER_CLASSES",
+            PolicyViolation::LowEntropy { .. } => "PASSWORD_TOO_WEAK",
+            PolicyViolation::ContainsUsername => "PASSWORD_CONTAINS_USERNAME",
+            PolicyViolation::Blocklisted => "PASSWORD_BLOCKLISTED",
+        }
+    }
+}
+
+impl std::fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyViolation::TooShort { min } => write!(f, "密码长度不能少于 {min} 个字符"),
+            PolicyViolation::TooLong { max } => write!(f, "密码长度不能超过 {max} 个字符"),
+            PolicyViolation::MissingLowercase => write!(f, "密码必须包含小写字母"),
+            PolicyViolation::MissingUppercase => write!(f, "密码必须包含大写字母"),
+            PolicyViolation::MissingDigit => write!(f, "密码必须包含数字"),
+            PolicyViolation::MissingSymbol => write!(f, "密码必须包含符号"),
+            PolicyViolation::TooFewCharacterClasses { min } => {
+                write!(f, "密码至少要包含大小写字母、数字、符号中的 {min} 类")
+            }
+            PolicyViolation::LowEntropy { .. } => {
+                write!(f, "密码太容易被猜到，请避免重复或连续的字符")
+            }
+            PolicyViolation::ContainsUsername => write!(f, "密码不能包含用户名"),
+            PolicyViolation::Blocklisted => write!(f, "密码过于常见或已经泄露，请换一个"),
+        }
+    }
+}
+
+/// 密码强度策略
+///
+/// # 功能描述
+/// 按配置检查密码的长度、字符类别、估算的熵、是否包含用户名以及是否在常见密码列表中，
+/// 注册、修改密码和重置密码都使用同一份策略。
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    config: PasswordPolicyConfig,
+    blocklist: HashSet<String>,
+}
+
+impl PasswordPolicy {
+    /// 根据配置创建密码策略，配置了常见密码列表时从文件加载
+    pub fn new(config: PasswordPolicyConfig) -> anyhow::Result<Self> {
+        if config.min_length() > config.max_length() {
+            anyhow::bail!("password_policy.min_length is greater than max_length");
+        }
+        let blocklist = match config.blocklist_file() {
+            Some(path) => std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Failed to read password blocklist {path}: {e}"))?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_lowercase)
+                .collect(),
+            None => HashSet::new(),
+        };
+        Ok(Self { config, blocklist })
+    }
+
+    /// 检查密码，返回违反的全部规则
+    ///
+    /// # 参数
+    /// - `password`: 待检查的密码
+    /// - `username`: 用户名
+    pub fn check(&self, password: &str, username: &str) -> Vec<PolicyViolation> {
+        let config = &self.config;
+        let mut violations = Vec::new();
+        let length = password.chars().count();
+        if length < config.min_length() {
+            violations.push(PolicyViolation::TooShort {
+                min: config.min_length(),
+            });
+        }
+        if length > config.max_length() {
+            violations.push(PolicyViolation::TooLong {
+                max: config.max_length(),
+            });
+        }
+        let classes = CharacterClasses::of(password);
+        if config.require_lowercase() && !classes.lowercase {
+            violations.push(PolicyViolation::MissingLowercase);
+        }
+        if config.require_uppercase() && !classes.uppercase {
+            violations.push(PolicyViolation::MissingUppercase);
+        }
+        if config.require_digit() && !classes.digit {
+            violations.push(PolicyViolation::MissingDigit);
+        }
+        if config.require_symbol() && !classes.symbol {
+            violations.push(PolicyViolation::MissingSymbol);
+        }
+        if classes.count() < config.min_character_classes() {
+            violations.push(PolicyViolation::TooFewCharacterClasses {
+                min: config.min_character_classes(),
+            });
+        }
+        if estimate_entropy(password) < config.min_entropy_bits() {
+            violations.push(PolicyViolation::LowEntropy {
+                min_bits: config.min_entropy_bits(),
+            });
+        }
+        let lowercase = password.to_lowercase();
+        if config.reject_username()
+            && username.chars().count() >= MIN_USERNAME_CHECK_LEN
+            && lowercase.contains(&username.to_lowercase())
+        {
+            violations.push(PolicyViolation::ContainsUsername);
+        }
+        if self.blocklist.contains(&lowercase) {
+            violations.push(PolicyViolation::Blocklisted);
+        }
+        violations
+    }
+
+    /// 检查密码，不满足策略时返回携带 BadRequest 详情的 `InvalidArgument`
+    ///
+    /// # 参数
+    /// - `field`: 密码在请求中的字段名，写入错误详情
+    /// - `password`: 待检查的密码
+    /// - `username`: 用户名
+    pub fn validate(&self, field: &str, password: &str, username: &str) -> Result<(), Status> {
+        let violations = self.check(password, username);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let message = violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("；");
+        // tonic-types 编码 BadRequest 时会丢掉 FieldViolation.reason，这里直接编码 google.rpc.Status
+        let bad_request = pb::BadRequest {
+            field_violations: violations
+                .iter()
+                .map(|violation| pb::bad_request::FieldViolation {
+                    field: field.to_string(),
+                    description: violation.to_string(),
+                    reason: violation.reason().to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+        let message = format!("密码不符合要求：{message}");
+        let details = pb::Status {
+            code: Code::InvalidArgument as i32,
+            message: message.clone(),
+            details: vec![prost_types::Any {
+                type_url: BadRequest::TYPE_URL.to_string(),
+                value: bad_request.encode_to_vec(),
+            }],
+        };
+        Err(Status::with_details(
+            Code::InvalidArgument,
+            message,
+            details.encode_to_vec().into(),
+        ))
+    }
+}
+
+/// 密码包含的字符类别
+#[derive(Debug, Default)]
+struct CharacterClasses {
+    lowercase: bool,
+    uppercase: bool,
+    digit: bool,
+    symbol: bool,
+    other: bool,
+}
+
+impl CharacterClasses {
+    fn of(password: &str) -> Self {
+        let mut classes = Self::default();
+        for c in password.chars() {
+            match c {
+                'a'..='z' => classes.lowercase = true,
+                'A'..='Z' => classes.uppercase = true,
+                '0'..='9' => classes.digit = true,
+                c if c.is_ascii() => classes.symbol = true,
+                _ => classes.other = true,
+            }
+        }
+        classes
+    }
+
+    /// 包含的类别数，非 ASCII 字符计为符号
+    fn count(&self) -> usize {
+        [
+            self.lowercase,
+            self.uppercase,
+            self.digit,
+            self.symbol || self.other,
+        ]
+        .into_iter()
+        .filter(|present| *present)
+        .count()
+    }
+
+    /// 字符集大小
+    fn pool_size(&self) -> f64 {
+        let mut size = 0.0;
+        if self.lowercase {
+            size += 26.0;
+        }
+        if self.uppercase {
+            size += 26.0;
+        }
+        if self.digit {
+            size += 10.0;
+        }
+        if self.symbol {
+            size += 33.0;
+        }
+        if self.other {
+            size += 100.0;
+        }
+        size
+    }
+}
+
+/// 估算密码的熵（比特）
+///
+/// # 功能描述
+/// 按 `有效长度 * log2(字符集大小)` 估算，与前一个字符相同或连续（如 `abc`、`321`）的字符只计半个长度，
+/// 这样 `aaaaaaaa`、`12345678` 这类密码会被识别为弱密码。
+///
+/// # 参数
+/// - `password`: 密码
+///
+/// # 返回值
+/// 返回估算的熵
+pub fn estimate_entropy(password: &str) -> f64 {
+    let pool_size = CharacterClasses::of(password).pool_size();
+    if pool_size == 0.0 {
+        return 0.0;
+    }
+    let mut effective_length = 0.0;
+    let mut previous: Option<char> = None;
+    for c in password.chars() {
+        let predictable = previous.is_some_and(|p| {
+            let delta = c as i64 - p as i64;
+            (-1..=1).contains(&delta)
+        });
+        effective_length += if predictable { 0.5 } else { 1.0 };
+        previous = Some(c);
+    }
+    effective_length * pool_size.log2()
+}
@@ -0,0 +1,222 @@
+use std::{net::IpAddr, time::Duration};
+
+use mobc_redis::redis::AsyncCommands;
+use sqlx::{
+    PgConnection, PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use tonic::{Code, Status, metadata::MetadataValue};
+use tracing::Instrument;
+
+use crate::{
+    conf::mail::MailConfig,
+    db::{error::db_error, get_global_redis_pool, pgsql, trace::db_span},
+    mail::{Mail, Mailer},
+    response::error_code::ErrorCode,
+    service_impl::login_limit::RETRY_AFTER_METADATA,
+    utils::crypto::{generate_token, hash_token},
+};
+
+/// 数据库错误日志中的操作说明
+const DB_CONTEXT: &str = "重置密码令牌数据库操作失败";
+/// 帐号冷却期的 key 前缀，后面拼接用户 id
+const COOLDOWN_PREFIX: &str = "auth:password_reset_cooldown:";
+/// IP 申请次数的 key 前缀，后面拼接 IP
+const IP_COUNT_PREFIX: &str = "auth:password_reset_ip:";
+
+/// password_reset_token 表中的一条记录
+#[derive(Debug, sqlx::FromRow)]
+struct PasswordResetRecord {
+    user_id: i32,
+    expires_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+}
+
+/// 签发重置密码令牌
+///
+/// # 功能描述
+/// 生成随机令牌，数据库中只保存它的 SHA-256 摘要，原始令牌只出现在邮件中。
+/// 同一个用户之前未使用的令牌全部作废，只有最新的一封邮件有效。
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `user_id`: 用户 id
+/// - `ttl`: 令牌有效期
+pub async fn issue(pool: &PgPool, user_id: i32, ttl: Duration) -> Result<String, Status> {
+    let token = generate_token();
+    let mut tx = pgsql::begin(pool).await?;
+    sqlx::query(
+        r#"UPDATE password_reset_token SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL"#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .instrument(db_span("UPDATE", "password_reset_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    sqlx::query(
+        r#"INSERT INTO password_reset_token (user_id, token_hash, expires_at) VALUES ($1, $2, NOW() + make_interval(secs => $3))"#,
+    )
+    .bind(user_id)
+    .bind(hash_token(&token))
+    .bind(ttl.as_secs_f64())
+    .execute(&mut *tx)
+    .instrument(db_span("INSERT", "password_reset_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    tx.commit().await.map_err(db_error(DB_CONTEXT))?;
+    Ok(token)
+}
+
+/// 使用重置密码令牌
+///
+/// # 功能描述
+/// 在调用方的事务中锁定并校验令牌，校验通过后把该用户未使用的令牌全部标记为已使用，
+/// 保证令牌只能使用一次。
+/// 令牌不存在、已使用或已过期时返回同样的错误，不泄露令牌的状态。
+///
+/// # 参数
+/// - `conn`: 事务中的数据库连接
+/// - `token`: 邮件中的原始令牌
+///
+/// # 返回值
+/// 返回令牌所属的用户 id
+pub async fn consume(conn: &mut PgConnection, token: &str) -> Result<i32, Status> {
+    let invalid = || {
+        ErrorCode::InvalidToken.status(Code::InvalidArgument, "重置链接无效或已过期，请重新申请！")
+    };
+    let record = sqlx::query_as::<_, PasswordResetRecord>(
+        r#"SELECT user_id, expires_at, used_at FROM password_reset_token WHERE token_hash = $1 FOR UPDATE"#,
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&mut *conn)
+    .instrument(db_span("SELECT", "password_reset_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?
+    .ok_or_else(invalid)?;
+    if record.used_at.is_some() || record.expires_at <= Utc::now() {
+        return Err(invalid());
+    }
+    // 同一用户其他未使用的令牌一并作废
+    sqlx::query(
+        r#"UPDATE password_reset_token SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL"#,
+    )
+    .bind(record.user_id)
+    .execute(&mut *conn)
+    .instrument(db_span("UPDATE", "password_reset_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    Ok(record.user_id)
+}
+
+/// 统计 IP 申请重置密码的次数
+///
+/// # 功能描述
+/// 每次申请计数加一，统计窗口内超过 `password_reset_ip_limit` 次时拒绝，
+/// 限制的是 IP 而不是帐号，被拒绝时不会暴露帐号是否存在。
+///
+/// # 参数
+/// - `ip`: 客户端 IP
+/// - `config`: 邮件配置
+///
+/// # 返回值
+/// 超过次数时返回携带 `retry-after` 的 `ResourceExhausted`
+pub async fn check_ip_limit(ip: IpAddr, config: &MailConfig) -> anyhow::Result<Result<(), Status>> {
+    let key = ip_limit_key(ip);
+    let mut conn = get_global_redis_pool().get().await?;
+    let (count, mut ttl): (u64, i64) = mobc_redis::redis::pipe()
+        .atomic()
+        .incr(&key, 1)
+        .ttl(&key)
+        .query_async(&mut *conn)
+        .await?;
+    // 还没有过期时间说明是窗口内的第一次申请，窗口从这时开始计算
+    if ttl < 0 {
+        ttl = config.password_reset_ip_window_secs().max(1) as i64;
+        let _: () = conn.expire(&key, ttl).await?;
+    }
+    if count > config.password_reset_ip_limit() {
+        return Ok(Err(too_many_requests(ttl.max(1) as u64)));
+    }
+    Ok(Ok(()))
+}
+
+/// IP 申请次数的 key，每个客户端 IP 单独计数
+pub fn ip_limit_key(ip: IpAddr) -> String {
+    format!("{IP_COUNT_PREFIX}{ip}")
+}
+
+/// 申请重置密码过于频繁，`retry-after` 为剩余的等待时间（秒）
+pub fn too_many_requests(retry_after: u64) -> Status {
+    let mut status =
+        Status::resource_exhausted(format!("申请过于频繁，请在 {retry_after} 秒后重试！"));
+    status
+        .metadata_mut()
+        .insert(RETRY_AFTER_METADATA, MetadataValue::from(retry_after));
+    status
+}
+
+/// 进入帐号的冷却期
+///
+/// # 返回值
+/// 成功进入冷却期时返回 `true`，帐号已经在冷却期内时返回 `false`
+async fn start_cooldown(user_id: i32, cooldown_secs: u64) -> anyhow::Result<bool> {
+    let mut conn = get_global_redis_pool().get().await?;
+    let started: Option<String> = conn
+        .set_options(
+            format!("{COOLDOWN_PREFIX}{user_id}"),
+            1,
+            mobc_redis::redis::SetOptions::default()
+                .conditional_set(mobc_redis::redis::ExistenceCheck::NX)
+                .with_expiration(mobc_redis::redis::SetExpiry::EX(cooldown_secs.max(1))),
+        )
+        .await?;
+    Ok(started.is_some())
+}
+
+/// 向用户发送重置密码邮件
+///
+/// # 功能描述
+/// 按用户名查询启用中且邮箱已验证的用户，签发重置令牌并发送包含重置链接的邮件。
+/// 用户不存在、已禁用或邮箱未验证时直接返回，调用方看不到任何差别。
+/// 同一个帐号在冷却期内只发一封邮件，避免被人用来轰炸邮箱或不断作废用户收到的链接。
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `mailer`: 邮件发送器
+/// - `config`: 邮件配置
+/// - `username`: 用户名
+pub async fn send_reset_mail(
+    pool: &PgPool,
+    mailer: &dyn Mailer,
+    config: &MailConfig,
+    username: &str,
+) -> anyhow::Result<()> {
+    let user = sqlx::query_as::<_, (i32, String)>(
+        r#"SELECT id, email FROM "user" WHERE username = $1 AND is_open AND email_verified_at IS NOT NULL"#,
+    )
+    .bind(username)
+    .fetch_optional(&mut *pgsql::acquire(pool).await?)
+    .instrument(db_span("SELECT", "user"))
+    .await?;
+    let Some((user_id, email)) = user else {
+        tracing::info!("password reset requested for unknown account: {}", username);
+        return Ok(());
+    };
+    if !start_cooldown(user_id, config.password_reset_cooldown_secs()).await? {
+        tracing::info!("password reset requested during cooldown: {}", username);
+        return Ok(());
+    }
+    let ttl = Duration::from_secs(config.password_reset_ttl_secs());
+    let token = issue(pool, user_id, ttl).await?;
+    let url = config.password_reset_url().replace("{token}", &token);
+    mailer
+        .send(Mail {
+            to: email,
+            subject: "重置密码".to_string(),
+            body: format!(
+                "{username}，你好：\n\n请在 {} 分钟内打开下面的链接重置密码：\n{url}\n\n如果这不是你本人的操作，请忽略这封邮件。\n",
+                ttl.as_secs() / 60
+            ),
+        })
+        .await
+}
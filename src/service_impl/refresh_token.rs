@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use sqlx::{
+    PgExecutor, PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use tonic::{Code, Status};
+use tracing::Instrument;
+
+use crate::{
+    db::{error::db_error, pgsql, trace::db_span},
+    response::error_code::ErrorCode,
+    utils::crypto::{generate_token, hash_token},
+};
+
+/// 数据库错误日志中的操作说明
+const DB_CONTEXT: &str = "refresh token 数据库操作失败";
+
+/// refresh_token 表中的一条记录
+#[derive(Debug, sqlx::FromRow)]
+struct RefreshTokenRecord {
+    id: i64,
+    user_id: i32,
+    family_id: String,
+    expires_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+/// 轮换后的 refresh token
+///
+/// # 成员
+/// - user_id: 令牌所属的用户 id
+/// - family_id: 令牌族 id，轮换前后保持不变
+/// - token: 新签发的原始令牌，只返回给客户端，不落库
+#[derive(Debug)]
+pub struct RotatedToken {
+    pub user_id: i32,
+    pub family_id: String,
+    pub token: String,
+}
+
+/// 签发一个新的 refresh token
+///
+/// # 功能描述
+/// 生成随机令牌，数据库中只保存它的 SHA-256 摘要，原始令牌返回给调用方。
+///
+/// # 参数
+/// - `executor`: 数据库连接或事务
+/// - `user_id`: 用户 id
+/// - `family_id`: 令牌族 id，每次登录生成一个新的令牌族，轮换时沿用
+/// - `ttl`: 令牌有效期
+pub async fn issue<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: i32,
+    family_id: &str,
+    ttl: Duration,
+) -> Result<String, Status> {
+    let token = generate_token();
+    sqlx::query(
+        r#"INSERT INTO refresh_token (user_id, family_id, token_hash, expires_at) VALUES ($1, $2, $3, NOW() + make_interval(secs => $4))"#,
+    )
+    .bind(user_id)
+    .bind(family_id)
+    .bind(hash_token(&token))
+    .bind(ttl.as_secs_f64())
+    .execute(executor)
+    .instrument(db_span("INSERT", "refresh_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    Ok(token)
+}
+
+/// 轮换 refresh token
+///
+/// # 功能描述
+/// 校验传入的 refresh token，将其标记为已使用，并在同一令牌族中签发一个新的令牌。
+/// 如果传入的令牌已经被使用或吊销，说明令牌可能已经泄露，此时吊销整个令牌族。
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `token`: 客户端提交的原始 refresh token
+/// - `ttl`: 新令牌的有效期
+pub async fn rotate(pool: &PgPool, token: &str, ttl: Duration) -> Result<RotatedToken, Status> {
+    let mut tx = pgsql::begin(pool).await?;
+    // 1. 查询并锁定令牌记录
+    let record = sqlx::query_as::<_, RefreshTokenRecord>(
+        r#"SELECT id, user_id, family_id, expires_at, used_at, revoked_at FROM refresh_token WHERE token_hash = $1 FOR UPDATE"#,
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&mut *tx)
+    .instrument(db_span("SELECT", "refresh_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?
+    .ok_or_else(|| ErrorCode::SessionExpired.status(Code::Unauthenticated, "refresh token 无效！"))?;
+    // 2. 令牌被重复使用，吊销整个令牌族
+    if record.used_at.is_some() || record.revoked_at.is_some() {
+        revoke_family(&mut *tx, &record.family_id).await?;
+        tx.commit().await.map_err(db_error(DB_CONTEXT))?;
+        tracing::warn!(
+            "refresh token reuse detected, user_id: {}, family_id: {}",
+            record.user_id,
+            record.family_id
+        );
+        return Err(
+            ErrorCode::SessionExpired.status(Code::Unauthenticated, "登录已失效，请重新登录！")
+        );
+    }
+    // 3. 检查是否过期
+    if record.expires_at <= Utc::now() {
+        return Err(
+            ErrorCode::SessionExpired.status(Code::Unauthenticated, "登录已过期，请重新登录！")
+        );
+    }
+    // 4. 标记为已使用，并签发新令牌
+    sqlx::query(r#"UPDATE refresh_token SET used_at = NOW() WHERE id = $1"#)
+        .bind(record.id)
+        .execute(&mut *tx)
+        .instrument(db_span("UPDATE", "refresh_token"))
+        .await
+        .map_err(db_error(DB_CONTEXT))?;
+    let token = issue(&mut *tx, record.user_id, &record.family_id, ttl).await?;
+    tx.commit().await.map_err(db_error(DB_CONTEXT))?;
+    Ok(RotatedToken {
+        user_id: record.user_id,
+        family_id: record.family_id,
+        token,
+    })
+}
+
+/// 吊销整个令牌族
+pub async fn revoke_family<'e>(
+    executor: impl PgExecutor<'e>,
+    family_id: &str,
+) -> Result<(), Status> {
+    sqlx::query(
+        r#"UPDATE refresh_token SET revoked_at = NOW() WHERE family_id = $1 AND revoked_at IS NULL"#,
+    )
+    .bind(family_id)
+    .execute(executor)
+    .instrument(db_span("UPDATE", "refresh_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    Ok(())
+}
+
+/// 吊销 refresh token 所在的整个令牌族
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `token`: 客户端提交的原始 refresh token
+/// - `user_id`: 当前用户 id，只能吊销属于自己的令牌
+pub async fn revoke_by_token(pool: &PgPool, token: &str, user_id: i32) -> Result<(), Status> {
+    sqlx::query(
+        r#"UPDATE refresh_token SET revoked_at = NOW() WHERE revoked_at IS NULL AND family_id = (SELECT family_id FROM refresh_token WHERE token_hash = $1 AND user_id = $2)"#,
+    )
+    .bind(hash_token(token))
+    .bind(user_id)
+    .execute(&mut *pgsql::acquire(pool).await?)
+    .instrument(db_span("UPDATE", "refresh_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    Ok(())
+}
+
+/// 吊销用户的全部 refresh token
+pub async fn revoke_user<'e>(executor: impl PgExecutor<'e>, user_id: i32) -> Result<(), Status> {
+    sqlx::query(
+        r#"UPDATE refresh_token SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL"#,
+    )
+    .bind(user_id)
+    .execute(executor)
+    .instrument(db_span("UPDATE", "refresh_token"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?;
+    Ok(())
+}
@@ -1,21 +1,70 @@
 use std::{ops::Deref, sync::Arc};
 
-use sqlx::PgPool;
-use tonic::{Request, Response, Status};
+use sqlx::{
+    PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use tonic::{Code, Request, Response, Status};
+use tracing::Instrument;
+use validator::ValidateEmail;
 
 use crate::{
-    middlewares::auth::{identity::Identity, jwt::get_default_jwt, principal::Principal},
+    common::client_info::ClientInfo,
+    conf::{enumeration::EnumerationConfig, mail::MailConfig, register::RegisterConfig},
+    db::{
+        error::{db_error, db_status},
+        pgsql,
+        trace::db_span,
+    },
+    mail::Mailer,
+    metrics,
+    middlewares::auth::{
+        guard::require_grpc_identity, identity::Identity, jwt::get_global_jwt,
+        principal::Principal, revocation,
+    },
     pb::user::{
-        UserExistsRequest, UserExistsResponse, UserLoginRequest, UserLoginResponse,
-        UserRegisterRequest, UserRegisterResponse, user_service_server::UserService,
+        ChangePasswordRequest, ClearLoginLockoutRequest, ClearLoginLockoutResponse,
+        ConfirmPasswordResetRequest, EmailVerificationResponse, GetUserRequest,
+        ListLoginHistoryRequest, ListLoginHistoryResponse, ListUsersRequest, ListUsersResponse,
+        PasswordResetResponse, RefreshTokenRequest, RequestPasswordResetRequest,
+        RevokeUserSessionsRequest, RevokeUserSessionsResponse, SendEmailVerificationRequest,
+        SetUserLevelRequest, SetUserStatusRequest, UserExistsRequest, UserExistsResponse, UserInfo,
+        UserLoginRequest, UserLoginResponse, UserLogoutRequest, UserLogoutResponse,
+        UserRegisterRequest, UserRegisterResponse, VerifyEmailRequest,
+        user_service_server::UserService,
+    },
+    response::error_code::ErrorCode,
+    service_impl::{
+        email_verification,
+        login_history::{self, LoginOutcome},
+        login_limit::{self, LoginLimiter, login_subject},
+        password_policy::PasswordPolicy,
+        password_reset, refresh_token,
+        user_admin::{self, UserFilter},
+    },
+    utils::{
+        crypto::{encode_password, needs_rehash, verify_dummy_password, verify_password},
+        timezone::east8,
     },
-    utils::crypto::{encode_password, verify_password},
 };
 
+/// 邮箱的唯一索引
+const USER_EMAIL_UNIQUE_INDEX: &str = "idx_user_email_unique";
+/// 登录历史默认返回的记录数
+const DEFAULT_HISTORY_LIMIT: i32 = 20;
+/// 登录历史最多返回的记录数
+const MAX_HISTORY_LIMIT: i32 = 100;
+
 /// 内部数据状态
 #[derive(Debug, Clone)]
 pub struct AppStateInner {
     pub pool: &'static PgPool,
+    pub login_limiter: LoginLimiter,
+    pub mailer: Arc<dyn Mailer>,
+    pub mail_config: MailConfig,
+    pub register_config: RegisterConfig,
+    pub password_policy: PasswordPolicy,
+    pub enumeration_config: EnumerationConfig,
 }
 
 // 实现 UserService trait
@@ -26,11 +75,117 @@ pub struct UserServiceImpl {
 }
 
 impl UserServiceImpl {
-    pub fn new(pool: &'static PgPool) -> Self {
+    pub fn new(
+        pool: &'static PgPool,
+        login_limiter: LoginLimiter,
+        mailer: Arc<dyn Mailer>,
+        mail_config: MailConfig,
+        register_config: RegisterConfig,
+        password_policy: PasswordPolicy,
+        enumeration_config: EnumerationConfig,
+    ) -> Self {
         Self {
-            inner: Arc::new(AppStateInner { pool }),
+            inner: Arc::new(AppStateInner {
+                pool,
+                login_limiter,
+                mailer,
+                mail_config,
+                register_config,
+                password_policy,
+                enumeration_config,
+            }),
+        }
+    }
+
+    /// 把注册时违反唯一约束的错误转换成 `AlreadyExists`
+    ///
+    /// # 功能描述
+    /// 加固模式下不区分是用户名还是邮箱已被使用，其他数据库错误按 SQLSTATE 归类。
+    fn register_conflict(&self, e: sqlx::Error) -> Status {
+        let constraint = e
+            .as_database_error()
+            .filter(|db_error| db_error.is_unique_violation())
+            .map(|db_error| db_error.constraint().unwrap_or_default());
+        match constraint {
+            Some(_) if self.inner.enumeration_config.hardened() => {
+                ErrorCode::UserAlreadyExists.status(Code::AlreadyExists, "用户名或邮箱已被注册！")
+            }
+            Some(USER_EMAIL_UNIQUE_INDEX) => {
+                ErrorCode::EmailAlreadyExists.status(Code::AlreadyExists, "该邮箱已被注册！")
+            }
+            Some(_) => {
+                ErrorCode::UserAlreadyExists.status(Code::AlreadyExists, "要注册的帐号已经存在！")
+            }
+            None => db_status("创建用户失败", e),
+        }
+    }
+
+    /// 使用当前的哈希参数重新哈希密码
+    ///
+    /// # 功能描述
+    /// 只在密码验证通过后调用。更新时带上旧的哈希作为条件，期间密码被修改则放弃；
+    /// 失败只记录日志，不影响本次登录。
+    async fn rehash_password(&self, user_info: &UserLoginInfo, password: &str) {
+        let hash_password = match encode_password(password) {
+            Ok(hash_password) => hash_password,
+            Err(e) => {
+                tracing::error!("重新哈希密码失败: {:?}", e);
+                return;
+            }
+        };
+        // 获取连接失败时已经记录日志
+        let Ok(mut conn) = pgsql::acquire(self.inner.pool).await else {
+            return;
+        };
+        let result =
+            sqlx::query(r#"UPDATE "user" SET password = $1 WHERE id = $2 AND password = $3"#)
+                .bind(&hash_password)
+                .bind(user_info.id)
+                .bind(&user_info.password)
+                .execute(&mut *conn)
+                .instrument(db_span("UPDATE", "user"))
+                .await;
+        match result {
+            Ok(_) => tracing::info!("password of user {} rehashed", user_info.id),
+            Err(e) => tracing::error!("更新重新哈希的密码失败: {:?}", e),
         }
     }
+
+    /// 在后台签发邮箱验证令牌并发送验证邮件，发送失败只记录日志
+    fn spawn_verification_mail(&self, user_id: i32, username: String, email: String) {
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            if let Err(e) = email_verification::send_verification_mail(
+                inner.pool,
+                inner.mailer.as_ref(),
+                &inner.mail_config,
+                user_id,
+                &username,
+                &email,
+            )
+            .await
+            {
+                tracing::error!("发送验证邮件失败: {:?}", e);
+            }
+        });
+    }
+
+    /// 为 principal 生成 access_token，并与 refresh_token 一起组装成登录响应
+    fn build_login_response(
+        &self,
+        principal: Principal,
+        refresh_token: String,
+    ) -> Result<UserLoginResponse, Status> {
+        let jwt = get_global_jwt();
+        let access_token = jwt
+            .encode(principal)
+            .map_err(internal_error("生成 token 失败"))?;
+        Ok(UserLoginResponse {
+            access_token,
+            refresh_token,
+            expires_in: jwt.expiration().as_secs() as i64,
+        })
+    }
 }
 #[derive(Debug, sqlx::FromRow, Clone)]
 pub struct UserLoginInfo {
@@ -41,6 +196,39 @@ pub struct UserLoginInfo {
     pub level: Identity,
 }
 
+/// 用户资料
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct UserProfile {
+    pub id: i32,
+    pub username: String,
+    pub email: Option<String>,
+    pub level: Identity,
+    pub is_open: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_login: Option<DateTime<Utc>>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+}
+
+/// 用户资料转换成 gRPC 响应，时间统一转换为东八区的 RFC 3339 格式
+impl From<UserProfile> for UserInfo {
+    fn from(value: UserProfile) -> Self {
+        let format_time = |time: DateTime<Utc>| match east8() {
+            Some(offset) => time.with_timezone(&offset).to_rfc3339(),
+            None => time.to_rfc3339(),
+        };
+        UserInfo {
+            id: value.id,
+            username: value.username,
+            email: value.email,
+            level: value.level.as_str().to_string(),
+            created_at: format_time(value.created_at),
+            last_login: value.last_login.map(format_time),
+            email_verified: value.email_verified_at.is_some(),
+            is_open: value.is_open,
+        }
+    }
+}
+
 /// 实现解引用操作
 impl Deref for UserServiceImpl {
     type Target = AppStateInner;
@@ -55,62 +243,161 @@ impl UserService for UserServiceImpl {
         &self,
         request: Request<UserLoginRequest>,
     ) -> std::result::Result<Response<UserLoginResponse>, Status> {
+        let client = ClientInfo::from_grpc(&request);
+        let ip = client.ip.map(|ip| ip.to_string());
+        let ip = ip.as_deref();
         let user_info_request = request.into_inner();
-        // 1. 查询用户信息
-        let user_info = sqlx::query_as::<_, UserLoginInfo>(
-            r#"SELECT id, username, password, is_open, level FROM "user" WHERE username = $1"#,
-        )
-        .bind(user_info_request.username)
-        .fetch_one(self.inner.pool)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::RowNotFound => Status::unauthenticated("帐号或密码不正确！"),
-            _ => {
-                tracing::error!("查询用户失败: {:?}", e);
-                Status::internal("服务器内部错误")
+        let username = user_info_request.username.as_str();
+        let pool = self.inner.pool;
+        let limiter = &self.inner.login_limiter;
+        // 1. 查询用户信息，用户名不能包含 @，包含 @ 时按已验证的邮箱查询
+        let sql = if username.contains('@') {
+            r#"SELECT id, username, password, is_open, level FROM "user" WHERE LOWER(email) = LOWER($1) AND email_verified_at IS NOT NULL"#
+        } else {
+            r#"SELECT id, username, password, is_open, level FROM "user" WHERE username = $1"#
+        };
+        let user_info = sqlx::query_as::<_, UserLoginInfo>(sql)
+            .bind(username)
+            .fetch_optional(&mut *pgsql::acquire(pool).await?)
+            .instrument(db_span("SELECT", "user"))
+            .await
+            .map_err(db_error("查询用户失败"))?;
+        // 2. 检查账号和 IP 是否被锁定，用户名和各种大小写的邮箱共用同一个账号的失败次数
+        let subject = login_subject(user_info.as_ref().map(|u| u.username.as_str()), username);
+        let username = subject.as_str();
+        if let Err(status) = limiter.check(username, ip).await {
+            if status.code() == Code::ResourceExhausted {
+                let user_id = user_info.as_ref().map(|u| u.id);
+                login_history::record(pool, user_id, username, LoginOutcome::Locked, &client).await;
             }
-        })?;
-        // 2. 检查用户状态（如是否被禁用）
-        if !user_info.is_open {
-            return Err(Status::permission_denied("该账号已被禁用，请联系管理员！"));
+            return Err(status);
+        }
+        let hardened = self.inner.enumeration_config.hardened();
+        let Some(user_info) = user_info else {
+            // 加固模式下同样执行一次密码验证，使响应时间与密码错误时一致
+            if hardened {
+                verify_dummy_password(&user_info_request.password);
+            }
+            login_history::record(pool, None, username, LoginOutcome::UnknownUser, &client).await;
+            limiter.record_failure(username, ip).await?;
+            return Err(
+                ErrorCode::InvalidCredentials.status(Code::Unauthenticated, "帐号或密码不正确！")
+            );
+        };
+        let user_id = Some(user_info.id);
+        // 3. 检查用户状态（如是否被禁用），加固模式下密码正确后才提示，避免暴露用户名是否存在
+        if !user_info.is_open && !hardened {
+            login_history::record(pool, user_id, username, LoginOutcome::Disabled, &client).await;
+            return Err(ErrorCode::AccountDisabled
+                .status(Code::PermissionDenied, "该账号已被禁用，请联系管理员！"));
         }
-        // 3. 验证密码
+        // 4. 验证密码，失败次数过多时锁定
         if !verify_password(&user_info_request.password, &user_info.password)
-            .map_err(|e| Status::internal(format!("Failed to verify password: {}", e)))?
+            .map_err(internal_error("验证密码失败"))?
         {
-            return Err(Status::unauthenticated("帐号或密码不正确！"));
+            login_history::record(pool, user_id, username, LoginOutcome::BadPassword, &client)
+                .await;
+            limiter.record_failure(username, ip).await?;
+            return Err(
+                ErrorCode::InvalidCredentials.status(Code::Unauthenticated, "帐号或密码不正确！")
+            );
+        }
+        if !user_info.is_open {
+            login_history::record(pool, user_id, username, LoginOutcome::Disabled, &client).await;
+            return Err(ErrorCode::AccountDisabled
+                .status(Code::PermissionDenied, "该账号已被禁用，请联系管理员！"));
+        }
+        limiter.record_success(username).await?;
+        // 密码哈希参数已调整或是导入的旧算法时，使用当前参数重新哈希
+        if needs_rehash(&user_info.password) {
+            self.rehash_password(&user_info, &user_info_request.password)
+                .await;
         }
-        // 4. 构建 principal
+        // 5. 更新最后登录时间并记录登录历史
+        sqlx::query(r#"UPDATE "user" SET last_login = NOW() WHERE id = $1"#)
+            .bind(user_info.id)
+            .execute(&mut *pgsql::acquire(pool).await?)
+            .instrument(db_span("UPDATE", "user"))
+            .await
+            .map_err(db_error("更新最后登录时间失败"))?;
+        login_history::record(pool, user_id, username, LoginOutcome::Success, &client).await;
+        // 6. 构建 principal
         let principal = Principal {
             id: user_info.id,
             username: user_info.username.clone(),
             identity: user_info.level,
         };
-        // 5. 生成 access_token
-        let access_token = get_default_jwt()
-            .encode(principal)
-            .map_err(|e| Status::internal(format!("Failed to encode JWT: {}", e)))?;
-        // 6. 返回 token
-        Ok(Response::new(UserLoginResponse { access_token }))
+        // 7. 生成 refresh_token，每次登录都开启一个新的令牌族
+        let family_id = xid::new().to_string();
+        let refresh_token = refresh_token::issue(
+            self.inner.pool,
+            user_info.id,
+            &family_id,
+            get_global_jwt().refresh_expiration(),
+        )
+        .await?;
+        // 8. 生成 access_token 并返回
+        Ok(Response::new(
+            self.build_login_response(principal, refresh_token)?,
+        ))
     }
     async fn user_register(
         &self,
         request: Request<UserRegisterRequest>,
     ) -> std::result::Result<Response<UserRegisterResponse>, Status> {
         let user_info = request.into_inner();
-        let hash_password = encode_password(&user_info.password).unwrap();
+        let pool = self.inner.pool;
+        // 1. 用户名不能包含 @，避免与邮箱登录混淆
+        if user_info.username.contains('@') {
+            return Err(Status::invalid_argument("用户名不能包含 @ 字符！"));
+        }
+        self.inner.password_policy.validate(
+            "password",
+            &user_info.password,
+            &user_info.username,
+        )?;
+        // 2. 校验邮箱，是否必填由配置决定
+        let email = user_info
+            .email
+            .as_deref()
+            .map(str::trim)
+            .filter(|email| !email.is_empty());
+        match email {
+            None if self.inner.register_config.email_required() => {
+                return Err(Status::invalid_argument("邮箱不能为空！"));
+            }
+            Some(email) if !email.validate_email() => {
+                return Err(Status::invalid_argument("邮箱格式不正确！"));
+            }
+            _ => {}
+        }
+        // 3. 创建用户，用户名和邮箱是否已被使用由唯一约束判断，避免先查询再插入的竞争
+        let hash_password =
+            encode_password(&user_info.password).map_err(internal_error("生成密码哈希失败"))?;
         let id: i32 = sqlx::query_scalar(
-            r#"INSERT INTO "user" (username, password) VALUES ($1, $2) RETURNING id"#,
+            r#"INSERT INTO "user" (username, password, email) VALUES ($1, $2, $3) RETURNING id"#,
         )
         .bind(&user_info.username)
         .bind(&hash_password)
-        .fetch_one(self.inner.pool)
+        .bind(email)
+        .fetch_one(&mut *pgsql::acquire(pool).await?)
+        .instrument(db_span("INSERT", "user"))
         .await
-        .map_err(|e| Status::internal(e.to_string()))?;
-
-        Ok(Response::new(UserRegisterResponse {
-            result: format!("{} 创建成功！id: {}", user_info.username, id),
-        }))
+        .map_err(|e| {
+            let status = self.register_conflict(e);
+            if status.code() == Code::AlreadyExists {
+                metrics::record_registration("conflict");
+            }
+            status
+        })?;
+        metrics::record_registration("success");
+        // 4. 填写了邮箱时在后台发送验证邮件
+        let mut result = format!("{} 创建成功！id: {}", user_info.username, id);
+        if let Some(email) = email {
+            self.spawn_verification_mail(id, user_info.username.clone(), email.to_string());
+            result.push_str("，验证邮件已发送，请查收！");
+        }
+        Ok(Response::new(UserRegisterResponse { result }))
     }
     async fn user_exists(
         &self,
@@ -122,10 +409,423 @@ impl UserService for UserServiceImpl {
             r#"SELECT EXISTS(SELECT 1 FROM "user" WHERE username = $1) as exists"#,
         )
         .bind(user_name)
-        .fetch_one(self.inner.pool)
+        .fetch_one(&mut *pgsql::acquire(self.inner.pool).await?)
+        .instrument(db_span("SELECT", "user"))
         .await
-        .map_err(|e| Status::internal(e.to_string()))?;
+        .map_err(db_error("查询用户名是否存在失败"))?;
 
         Ok(Response::new(result))
     }
+    async fn refresh_token(
+        &self,
+        request: Request<RefreshTokenRequest>,
+    ) -> std::result::Result<Response<UserLoginResponse>, Status> {
+        let refresh_token = request.into_inner().refresh_token;
+        // 1. 轮换 refresh_token，重复使用时会吊销整个令牌族
+        let rotated = refresh_token::rotate(
+            self.inner.pool,
+            &refresh_token,
+            get_global_jwt().refresh_expiration(),
+        )
+        .await?;
+        // 2. 重新查询用户信息，等级变更或禁用可以及时生效
+        let user_info = sqlx::query_as::<_, UserLoginInfo>(
+            r#"SELECT id, username, password, is_open, level FROM "user" WHERE id = $1"#,
+        )
+        .bind(rotated.user_id)
+        .fetch_optional(&mut *pgsql::acquire(self.inner.pool).await?)
+        .instrument(db_span("SELECT", "user"))
+        .await
+        .map_err(db_error("查询用户失败"))?;
+        let user_info = match user_info {
+            Some(user_info) if user_info.is_open => user_info,
+            _ => {
+                refresh_token::revoke_family(self.inner.pool, &rotated.family_id).await?;
+                return Err(ErrorCode::AccountDisabled
+                    .status(Code::PermissionDenied, "该账号已被禁用，请联系管理员！"));
+            }
+        };
+        // 3. 构建 principal 并返回新的 token
+        let principal = Principal {
+            id: user_info.id,
+            username: user_info.username,
+            identity: user_info.level,
+        };
+        Ok(Response::new(
+            self.build_login_response(principal, rotated.token)?,
+        ))
+    }
+    async fn user_logout(
+        &self,
+        request: Request<UserLogoutRequest>,
+    ) -> std::result::Result<Response<UserLogoutResponse>, Status> {
+        let logout_request = request.into_inner();
+        // 1. 校验 access_token
+        let claims = get_global_jwt()
+            .decode_claims(&logout_request.access_token)
+            .map_err(|_| Status::unauthenticated("没有登陆或登陆已过期"))?;
+        let principal = claims
+            .principal()
+            .map_err(|_| Status::unauthenticated("没有登陆或登陆已过期"))?;
+        // 2. 将 access_token 加入黑名单
+        revocation::revoke_token(&claims)
+            .await
+            .map_err(redis_error)?;
+        // 3. 吊销 refresh_token 所在的令牌族
+        if !logout_request.refresh_token.is_empty() {
+            refresh_token::revoke_by_token(
+                self.inner.pool,
+                &logout_request.refresh_token,
+                principal.id,
+            )
+            .await?;
+        }
+        Ok(Response::new(UserLogoutResponse {
+            result: String::from("退出登录成功！"),
+        }))
+    }
+    async fn revoke_user_sessions(
+        &self,
+        request: Request<RevokeUserSessionsRequest>,
+    ) -> std::result::Result<Response<RevokeUserSessionsResponse>, Status> {
+        let principal = require_grpc_identity(&request, &Identity::Guest)?;
+        let user_id = request.into_inner().user_id;
+        ensure_self_or_admin(&principal, user_id)?;
+        // 1. 吊销全部 refresh_token，不能再换取新的 access_token
+        refresh_token::revoke_user(self.inner.pool, user_id).await?;
+        // 2. 已经签发的 access_token 全部失效
+        revocation::revoke_user_sessions(user_id, get_global_jwt().expiration())
+            .await
+            .map_err(redis_error)?;
+        Ok(Response::new(RevokeUserSessionsResponse {
+            result: String::from("已退出全部登录会话！"),
+        }))
+    }
+    async fn get_user(
+        &self,
+        request: Request<GetUserRequest>,
+    ) -> std::result::Result<Response<UserInfo>, Status> {
+        let principal = require_grpc_identity(&request, &Identity::Guest)?;
+        let user_id = request.into_inner().user_id;
+        ensure_self_or_admin(&principal, user_id)?;
+        // 查询用户资料
+        let profile = user_admin::get(self.inner.pool, user_id).await?;
+        Ok(Response::new(profile.into()))
+    }
+    async fn list_login_history(
+        &self,
+        request: Request<ListLoginHistoryRequest>,
+    ) -> std::result::Result<Response<ListLoginHistoryResponse>, Status> {
+        let principal = require_grpc_identity(&request, &Identity::Guest)?;
+        let history_request = request.into_inner();
+        ensure_self_or_admin(&principal, history_request.user_id)?;
+        // 默认返回 20 条，最多返回 100 条
+        let limit = match history_request.limit {
+            limit if limit <= 0 => DEFAULT_HISTORY_LIMIT,
+            limit => limit.min(MAX_HISTORY_LIMIT),
+        };
+        let records =
+            login_history::list(self.inner.pool, history_request.user_id, limit as i64).await?;
+        Ok(Response::new(ListLoginHistoryResponse { records }))
+    }
+    async fn change_password(
+        &self,
+        request: Request<ChangePasswordRequest>,
+    ) -> std::result::Result<Response<UserLoginResponse>, Status> {
+        let principal = require_grpc_identity(&request, &Identity::Guest)?;
+        let client = ClientInfo::from_grpc(&request);
+        let ip = client.ip.map(|ip| ip.to_string());
+        let ip = ip.as_deref();
+        let change_request = request.into_inner();
+        let pool = self.inner.pool;
+        let limiter = &self.inner.login_limiter;
+        // 1. 与登录共用失败次数限制，防止借助修改密码暴力破解
+        limiter.check(&principal.username, ip).await?;
+        // 2. 查询用户信息
+        let user_info = sqlx::query_as::<_, UserLoginInfo>(
+            r#"SELECT id, username, password, is_open, level FROM "user" WHERE id = $1"#,
+        )
+        .bind(principal.id)
+        .fetch_optional(&mut *pgsql::acquire(pool).await?)
+        .instrument(db_span("SELECT", "user"))
+        .await
+        .map_err(db_error("查询用户失败"))?
+        .ok_or_else(|| Status::not_found("用户不存在！"))?;
+        if !user_info.is_open {
+            return Err(ErrorCode::AccountDisabled
+                .status(Code::PermissionDenied, "该账号已被禁用，请联系管理员！"));
+        }
+        // 3. 验证当前密码
+        if !verify_password(&change_request.current_password, &user_info.password)
+            .map_err(internal_error("验证密码失败"))?
+        {
+            limiter.record_failure(&user_info.username, ip).await?;
+            return Err(Status::invalid_argument("当前密码不正确！"));
+        }
+        if change_request.new_password == change_request.current_password {
+            return Err(Status::invalid_argument("新密码不能与当前密码相同！"));
+        }
+        self.inner.password_policy.validate(
+            "new_password",
+            &change_request.new_password,
+            &user_info.username,
+        )?;
+        // 4. 更新密码，吊销全部 refresh_token，并为当前会话签发新的 refresh_token
+        let hash_password = encode_password(&change_request.new_password)
+            .map_err(internal_error("生成密码哈希失败"))?;
+        let mut tx = pgsql::begin(pool).await?;
+        sqlx::query(r#"UPDATE "user" SET password = $1 WHERE id = $2"#)
+            .bind(&hash_password)
+            .bind(user_info.id)
+            .execute(&mut *tx)
+            .instrument(db_span("UPDATE", "user"))
+            .await
+            .map_err(db_error("更新密码失败"))?;
+        refresh_token::revoke_user(&mut *tx, user_info.id).await?;
+        let refresh_token = refresh_token::issue(
+            &mut *tx,
+            user_info.id,
+            &xid::new().to_string(),
+            get_global_jwt().refresh_expiration(),
+        )
+        .await?;
+        tx.commit().await.map_err(db_error("提交事务失败"))?;
+        // 5. 之前签发的 access_token 全部失效
+        revocation::revoke_user_sessions(user_info.id, get_global_jwt().expiration())
+            .await
+            .map_err(redis_error)?;
+        limiter.record_success(&user_info.username).await?;
+        // 6. 返回新的 token，当前会话保持登录
+        let principal = Principal {
+            id: user_info.id,
+            username: user_info.username,
+            identity: user_info.level,
+        };
+        Ok(Response::new(
+            self.build_login_response(principal, refresh_token)?,
+        ))
+    }
+    async fn request_password_reset(
+        &self,
+        request: Request<RequestPasswordResetRequest>,
+    ) -> std::result::Result<Response<PasswordResetResponse>, Status> {
+        let client = ClientInfo::from_grpc(&request);
+        let username = request.into_inner().username;
+        // 同一个 IP 申请次数过多时拒绝，同一个帐号的冷却期在后台发送邮件时检查
+        if let Some(ip) = client.ip {
+            password_reset::check_ip_limit(ip, &self.inner.mail_config)
+                .await
+                .map_err(redis_error)??;
+        }
+        // 查询用户、签发令牌和发送邮件都放到后台执行，
+        // 无论帐号是否存在，响应内容和耗时都一样，无法借此探测用户名
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            if let Err(e) = password_reset::send_reset_mail(
+                inner.pool,
+                inner.mailer.as_ref(),
+                &inner.mail_config,
+                &username,
+            )
+            .await
+            {
+                tracing::error!("发送重置密码邮件失败: {:?}", e);
+            }
+        });
+        Ok(Response::new(PasswordResetResponse {
+            result: String::from("如果该帐号存在并绑定了邮箱，重置密码的邮件已经发出，请查收！"),
+        }))
+    }
+    async fn confirm_password_reset(
+        &self,
+        request: Request<ConfirmPasswordResetRequest>,
+    ) -> std::result::Result<Response<PasswordResetResponse>, Status> {
+        let reset_request = request.into_inner();
+        let pool = self.inner.pool;
+        // 1. 在同一个事务中使用令牌、更新密码并吊销全部 refresh_token，
+        //    新密码不符合策略时事务回滚，令牌仍然可以使用
+        let mut tx = pgsql::begin(pool).await?;
+        let user_id = password_reset::consume(&mut tx, &reset_request.token).await?;
+        let username: String = sqlx::query_scalar(r#"SELECT username FROM "user" WHERE id = $1"#)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .instrument(db_span("SELECT", "user"))
+            .await
+            .map_err(db_error("查询用户失败"))?;
+        self.inner.password_policy.validate(
+            "new_password",
+            &reset_request.new_password,
+            &username,
+        )?;
+        let hash_password = encode_password(&reset_request.new_password)
+            .map_err(internal_error("生成密码哈希失败"))?;
+        sqlx::query(r#"UPDATE "user" SET password = $1 WHERE id = $2"#)
+            .bind(&hash_password)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .instrument(db_span("UPDATE", "user"))
+            .await
+            .map_err(db_error("更新密码失败"))?;
+        refresh_token::revoke_user(&mut *tx, user_id).await?;
+        tx.commit().await.map_err(db_error("提交事务失败"))?;
+        // 2. 之前签发的 access_token 全部失效，并解除登录锁定
+        revocation::revoke_user_sessions(user_id, get_global_jwt().expiration())
+            .await
+            .map_err(redis_error)?;
+        login_limit::clear_lockout(&username, None)
+            .await
+            .map_err(redis_error)?;
+        Ok(Response::new(PasswordResetResponse {
+            result: String::from("密码已重置，请使用新密码登录！"),
+        }))
+    }
+    async fn verify_email(
+        &self,
+        request: Request<VerifyEmailRequest>,
+    ) -> std::result::Result<Response<EmailVerificationResponse>, Status> {
+        let token = request.into_inner().token;
+        let user_id = email_verification::verify(self.inner.pool, &token).await?;
+        tracing::info!("email verified for user {}", user_id);
+        Ok(Response::new(EmailVerificationResponse {
+            result: String::from("邮箱验证成功，可以使用邮箱登录了！"),
+        }))
+    }
+    async fn send_email_verification(
+        &self,
+        request: Request<SendEmailVerificationRequest>,
+    ) -> std::result::Result<Response<EmailVerificationResponse>, Status> {
+        let principal = require_grpc_identity(&request, &Identity::Guest)?;
+        let (email, verified_at) = sqlx::query_as::<_, (Option<String>, Option<DateTime<Utc>>)>(
+            r#"SELECT email, email_verified_at FROM "user" WHERE id = $1"#,
+        )
+        .bind(principal.id)
+        .fetch_optional(&mut *pgsql::acquire(self.inner.pool).await?)
+        .instrument(db_span("SELECT", "user"))
+        .await
+        .map_err(db_error("查询用户失败"))?
+        .ok_or_else(|| Status::not_found("用户不存在！"))?;
+        let Some(email) = email else {
+            return Err(Status::failed_precondition("尚未填写邮箱！"));
+        };
+        if verified_at.is_some() {
+            return Err(Status::failed_precondition("邮箱已经验证过了！"));
+        }
+        self.spawn_verification_mail(principal.id, principal.username, email);
+        Ok(Response::new(EmailVerificationResponse {
+            result: String::from("验证邮件已发送，请查收！"),
+        }))
+    }
+    async fn clear_login_lockout(
+        &self,
+        request: Request<ClearLoginLockoutRequest>,
+    ) -> std::result::Result<Response<ClearLoginLockoutResponse>, Status> {
+        let principal = require_grpc_identity(&request, &Identity::Admin)?;
+        let clear_request = request.into_inner();
+        login_limit::clear_lockout(&clear_request.username, clear_request.ip.as_deref())
+            .await
+            .map_err(redis_error)?;
+        tracing::info!(
+            "admin {} cleared login lockout of {}",
+            principal.username,
+            clear_request.username
+        );
+        Ok(Response::new(ClearLoginLockoutResponse {
+            result: format!("已解除 {} 的登录锁定！", clear_request.username),
+        }))
+    }
+    async fn list_users(
+        &self,
+        request: Request<ListUsersRequest>,
+    ) -> std::result::Result<Response<ListUsersResponse>, Status> {
+        require_grpc_identity(&request, &Identity::Admin)?;
+        let filter = UserFilter::try_from(request.into_inner())?;
+        let (users, total) = user_admin::list(self.inner.pool, &filter).await?;
+        Ok(Response::new(ListUsersResponse {
+            users: users.into_iter().map(UserInfo::from).collect(),
+            total,
+            page: filter.page,
+            page_size: filter.page_size,
+        }))
+    }
+    async fn set_user_status(
+        &self,
+        request: Request<SetUserStatusRequest>,
+    ) -> std::result::Result<Response<UserInfo>, Status> {
+        let principal = require_grpc_identity(&request, &Identity::Admin)?;
+        let status_request = request.into_inner();
+        if status_request.user_id == principal.id {
+            return Err(Status::failed_precondition("不能修改自己的帐号状态！"));
+        }
+        let profile = user_admin::set_status(
+            self.inner.pool,
+            status_request.user_id,
+            status_request.is_open,
+        )
+        .await?;
+        // 禁用后立即吊销全部会话，不能再登录或刷新 token
+        if !profile.is_open {
+            refresh_token::revoke_user(self.inner.pool, profile.id).await?;
+            revocation::revoke_user_sessions(profile.id, get_global_jwt().expiration())
+                .await
+                .map_err(redis_error)?;
+        }
+        tracing::info!(
+            "admin {} set user {} is_open = {}",
+            principal.username,
+            profile.username,
+            profile.is_open
+        );
+        Ok(Response::new(profile.into()))
+    }
+    async fn set_user_level(
+        &self,
+        request: Request<SetUserLevelRequest>,
+    ) -> std::result::Result<Response<UserInfo>, Status> {
+        let principal = require_grpc_identity(&request, &Identity::Admin)?;
+        let level_request = request.into_inner();
+        if level_request.user_id == principal.id {
+            return Err(Status::failed_precondition("不能修改自己的用户等级！"));
+        }
+        let level = Identity::parse(&level_request.level).ok_or_else(|| {
+            Status::invalid_argument(format!("未知的用户等级：{}", level_request.level))
+        })?;
+        let profile = user_admin::set_level(self.inner.pool, level_request.user_id, &level).await?;
+        // access_token 中携带了旧的等级，吊销后客户端用 refresh_token 换取新等级的 token
+        revocation::revoke_user_sessions(profile.id, get_global_jwt().expiration())
+            .await
+            .map_err(redis_error)?;
+        tracing::info!(
+            "admin {} set user {} level = {}",
+            principal.username,
+            profile.username,
+            profile.level
+        );
+        Ok(Response::new(profile.into()))
+    }
+}
+
+/// 只允许操作自己的数据，管理员除外
+fn ensure_self_or_admin(principal: &Principal, user_id: i32) -> Result<(), Status> {
+    if principal.id == user_id || principal.has_identity(&Identity::Admin) {
+        Ok(())
+    } else {
+        Err(Status::permission_denied("不能操作其他用户的数据！"))
+    }
+}
+
+/// Redis 错误统一记录日志，对外只返回内部错误
+fn redis_error(e: anyhow::Error) -> Status {
+    internal_error("Redis 操作失败")(e)
+}
+
+/// 返回把内部错误（如生成 token、密码哈希失败）转换为 `tonic::Status` 的闭包，
+/// 错误详情只记录日志，对外返回与 `db_error` 相同的内部错误
+///
+/// # 参数
+/// - `context`: 日志中说明正在执行的操作
+fn internal_error<E: std::fmt::Debug>(context: &str) -> impl FnOnce(E) -> Status + '_ {
+    move |e| {
+        tracing::error!("{}: {:?}", context, e);
+        Status::internal("服务器内部错误")
+    }
 }
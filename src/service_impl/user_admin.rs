@@ -0,0 +1,204 @@
+use sqlx::{
+    PgPool, Postgres, QueryBuilder,
+    types::chrono::{DateTime, Utc},
+};
+use tonic::Status;
+use tracing::Instrument;
+
+use crate::{
+    db::{error::db_error, pgsql, trace::db_span},
+    middlewares::auth::identity::Identity,
+    pb::user::ListUsersRequest,
+    service_impl::user::UserProfile,
+};
+
+/// 数据库错误日志中的操作说明
+const DB_CONTEXT: &str = "用户管理数据库操作失败";
+
+/// 用户列表默认每页的记录数
+const DEFAULT_PAGE_SIZE: i32 = 20;
+/// 用户列表每页最多的记录数
+const MAX_PAGE_SIZE: i32 = 100;
+/// 查询用户资料的字段
+const PROFILE_COLUMNS: &str =
+    r#"id, username, email, level, is_open, created_at, last_login, email_verified_at"#;
+
+/// 用户列表的查询条件
+///
+/// # 成员
+/// - page: 页码，从 1 开始
+/// - page_size: 每页的记录数
+/// - level: 只返回该等级的用户
+/// - is_open: 只返回启用或禁用的用户
+/// - created_after / created_before: 创建时间范围，包含起点不包含终点
+/// - username_prefix: 用户名前缀
+#[derive(Debug, Default)]
+pub struct UserFilter {
+    pub page: i32,
+    pub page_size: i32,
+    pub level: Option<Identity>,
+    pub is_open: Option<bool>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub username_prefix: Option<String>,
+}
+
+impl TryFrom<ListUsersRequest> for UserFilter {
+    type Error = Status;
+
+    /// 校验并转换 gRPC 请求，页码和每页记录数超出范围时使用默认值或上限
+    fn try_from(value: ListUsersRequest) -> Result<Self, Self::Error> {
+        let level = value
+            .level
+            .map(|level| {
+                Identity::parse(&level)
+                    .ok_or_else(|| Status::invalid_argument(format!("未知的用户等级：{level}")))
+            })
+            .transpose()?;
+        Ok(UserFilter {
+            page: value.page.max(1),
+            page_size: match value.page_size {
+                size if size <= 0 => DEFAULT_PAGE_SIZE,
+                size => size.min(MAX_PAGE_SIZE),
+            },
+            level,
+            is_open: value.is_open,
+            created_after: value.created_after.as_deref().map(parse_time).transpose()?,
+            created_before: value
+                .created_before
+                .as_deref()
+                .map(parse_time)
+                .transpose()?,
+            username_prefix: value.username_prefix.filter(|prefix| !prefix.is_empty()),
+        })
+    }
+}
+
+impl UserFilter {
+    /// 把查询条件拼接到 SQL 的 WHERE 子句中
+    fn push_where<'a>(&'a self, builder: &mut QueryBuilder<'a, Postgres>) {
+        builder.push(" WHERE TRUE");
+        if let Some(level) = &self.level {
+            builder.push(" AND level = ").push_bind(level);
+        }
+        if let Some(is_open) = self.is_open {
+            builder.push(" AND is_open = ").push_bind(is_open);
+        }
+        if let Some(created_after) = self.created_after {
+            builder.push(" AND created_at >= ").push_bind(created_after);
+        }
+        if let Some(created_before) = self.created_before {
+            builder.push(" AND created_at < ").push_bind(created_before);
+        }
+        if let Some(prefix) = &self.username_prefix {
+            builder
+                .push(" AND username LIKE ")
+                .push_bind(format!("{}%", escape_like(prefix)))
+                .push(r" ESCAPE '\'");
+        }
+    }
+}
+
+/// 分页查询用户
+///
+/// # 参数
+/// - `pool`: 数据库连接池
+/// - `filter`: 查询条件
+///
+/// # 返回值
+/// 返回当前页的用户和满足条件的总数
+pub async fn list(pool: &PgPool, filter: &UserFilter) -> Result<(Vec<UserProfile>, i64), Status> {
+    let mut count = QueryBuilder::new(r#"SELECT COUNT(*) FROM "user""#);
+    filter.push_where(&mut count);
+    let total: i64 = count
+        .build_query_scalar()
+        .fetch_one(&mut *pgsql::acquire(pool).await?)
+        .instrument(db_span("SELECT", "user"))
+        .await
+        .map_err(db_error(DB_CONTEXT))?;
+
+    let mut query = QueryBuilder::new(format!(r#"SELECT {PROFILE_COLUMNS} FROM "user""#));
+    filter.push_where(&mut query);
+    query
+        .push(" ORDER BY id LIMIT ")
+        .push_bind(filter.page_size as i64)
+        .push(" OFFSET ")
+        .push_bind((filter.page as i64 - 1) * filter.page_size as i64);
+    let users = query
+        .build_query_as::<UserProfile>()
+        .fetch_all(&mut *pgsql::acquire(pool).await?)
+        .instrument(db_span("SELECT", "user"))
+        .await
+        .map_err(db_error(DB_CONTEXT))?;
+    Ok((users, total))
+}
+
+/// 查询单个用户的资料
+pub async fn get(pool: &PgPool, user_id: i32) -> Result<UserProfile, Status> {
+    sqlx::query_as::<_, UserProfile>(&format!(
+        r#"SELECT {PROFILE_COLUMNS} FROM "user" WHERE id = $1"#
+    ))
+    .bind(user_id)
+    .fetch_optional(&mut *pgsql::acquire(pool).await?)
+    .instrument(db_span("SELECT", "user"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?
+    .ok_or_else(|| Status::not_found("用户不存在！"))
+}
+
+/// 启用或禁用用户
+///
+/// # 返回值
+/// 返回修改后的用户资料
+pub async fn set_status(pool: &PgPool, user_id: i32, is_open: bool) -> Result<UserProfile, Status> {
+    sqlx::query_as::<_, UserProfile>(&format!(
+        r#"UPDATE "user" SET is_open = $1 WHERE id = $2 RETURNING {PROFILE_COLUMNS}"#
+    ))
+    .bind(is_open)
+    .bind(user_id)
+    .fetch_optional(&mut *pgsql::acquire(pool).await?)
+    .instrument(db_span("UPDATE", "user"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?
+    .ok_or_else(|| Status::not_found("用户不存在！"))
+}
+
+/// 修改用户等级
+///
+/// # 返回值
+/// 返回修改后的用户资料
+pub async fn set_level(
+    pool: &PgPool,
+    user_id: i32,
+    level: &Identity,
+) -> Result<UserProfile, Status> {
+    sqlx::query_as::<_, UserProfile>(&format!(
+        r#"UPDATE "user" SET level = $1 WHERE id = $2 RETURNING {PROFILE_COLUMNS}"#
+    ))
+    .bind(level)
+    .bind(user_id)
+    .fetch_optional(&mut *pgsql::acquire(pool).await?)
+    .instrument(db_span("UPDATE", "user"))
+    .await
+    .map_err(db_error(DB_CONTEXT))?
+    .ok_or_else(|| Status::not_found("用户不存在！"))
+}
+
+/// 解析 RFC 3339 格式的时间
+fn parse_time(value: &str) -> Result<DateTime<Utc>, Status> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| Status::invalid_argument(format!("时间格式不正确，应为 RFC 3339：{value}")))
+}
+
+/// 转义 LIKE 中的通配符
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -1,7 +1,225 @@
-use crate::response::ApiResult;
+use std::sync::OnceLock;
+
+use crate::{
+    conf::{password_hash::PasswordHashConfig, secret::resolve_secret},
+    response::ApiResult,
+};
 use argon2::{
-    Argon2, PasswordHash, PasswordHasher, PasswordVerifier, password_hash::phc::SaltString,
+    Algorithm, Argon2, KeyId, Params, ParamsBuilder, PasswordHash, PasswordHasher,
+    PasswordVerifier, Version, password_hash::phc::SaltString,
 };
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// 全局的密码哈希器，未初始化时使用 Argon2 的默认参数且不使用 pepper
+static PASSWORD_ENCODER: OnceLock<PasswordEncoder> = OnceLock::new();
+
+/// 使用配置初始化全局的密码哈希器
+///
+/// # 功能描述
+/// 在服务启动时调用一次，之后 `encode_password` 使用配置的参数和 pepper 生成哈希，
+/// `needs_rehash` 以配置的参数和 pepper 版本判断已有的哈希是否需要重新生成。
+/// 用户不存在时使用的占位哈希也在这里生成。
+///
+/// # 参数
+/// - `config`: 密码哈希配置
+pub fn init_password_hasher(config: &PasswordHashConfig) -> anyhow::Result<()> {
+    PASSWORD_ENCODER
+        .set(PasswordEncoder::new(config)?)
+        .map_err(|_| anyhow::anyhow!("password hasher already initialized"))
+}
+
+/// 获取全局的密码哈希器
+fn password_encoder() -> &'static PasswordEncoder {
+    PASSWORD_ENCODER.get_or_init(PasswordEncoder::default)
+}
+
+/// 一个版本的 pepper
+#[derive(Debug)]
+struct Pepper {
+    id: KeyId,
+    secret: Vec<u8>,
+}
+
+/// 密码哈希器
+///
+/// # 功能描述
+/// 使用 Argon2id 哈希密码。配置了 pepper 时，pepper 作为 Argon2 的 secret 参与哈希，
+/// 它的版本写入 PHC 字符串的 `keyid` 参数，验证时按版本选择 pepper，因此轮换后旧的哈希仍然可以验证。
+/// 没有 `keyid` 的哈希是未使用 pepper 生成的。
+#[derive(Debug)]
+pub struct PasswordEncoder {
+    params: Params,
+    /// 当前使用的 pepper 版本
+    current: Option<KeyId>,
+    /// 可用于验证的全部 pepper，包括当前和轮换前的版本
+    peppers: Vec<Pepper>,
+    /// 用户不存在时用于验证的哈希，创建时生成，避免第一次登录不存在的用户时多一次哈希耗时
+    dummy_hash: String,
+}
+
+impl Default for PasswordEncoder {
+    /// 使用 Argon2 的默认参数，不使用 pepper
+    fn default() -> Self {
+        Self::with_dummy_hash(Params::default(), None, Vec::new())
+            .expect("default argon2 params are valid")
+    }
+}
+
+impl PasswordEncoder {
+    /// 根据配置创建密码哈希器
+    ///
+    /// # 参数
+    /// - `config`: 密码哈希配置
+    ///
+    /// # 返回值
+    /// 参数超出范围、pepper 版本为空或超过 8 个字节、版本重复、读取 pepper 失败时返回错误
+    pub fn new(config: &PasswordHashConfig) -> anyhow::Result<Self> {
+        let mut peppers: Vec<Pepper> = Vec::new();
+        for pepper in config.pepper().into_iter().chain(config.previous_peppers()) {
+            if pepper.id().is_empty() {
+                anyhow::bail!("password_hash pepper id must not be empty");
+            }
+            let id = KeyId::new(pepper.id().as_bytes()).map_err(|_| {
+                anyhow::anyhow!(
+                    "password_hash pepper id {} is longer than 8 bytes",
+                    pepper.id()
+                )
+            })?;
+            if peppers.iter().any(|existing| existing.id == id) {
+                anyhow::bail!("duplicated password_hash pepper id {}", pepper.id());
+            }
+            let secret = resolve_secret(pepper.secret())?;
+            if secret.is_empty() {
+                anyhow::bail!("password_hash pepper {} is empty", pepper.id());
+            }
+            peppers.push(Pepper {
+                id,
+                secret: secret.into_bytes(),
+            });
+        }
+        let current = config.pepper().and_then(|_| peppers.first()).map(|p| p.id);
+        Self::with_dummy_hash(config.params()?, current, peppers)
+    }
+
+    /// 创建密码哈希器，并使用当前的参数和 pepper 生成占位哈希
+    fn with_dummy_hash(
+        params: Params,
+        current: Option<KeyId>,
+        peppers: Vec<Pepper>,
+    ) -> anyhow::Result<Self> {
+        let mut encoder = Self {
+            params,
+            current,
+            peppers,
+            dummy_hash: String::new(),
+        };
+        encoder.dummy_hash = encoder.encode(&generate_token())?;
+        Ok(encoder)
+    }
+
+    /// 使用当前的参数和 pepper 哈希密码
+    pub fn encode(&self, password: &str) -> ApiResult<String> {
+        // 生成随机 22 位 salt
+        let salt = SaltString::generate();
+        let mut params = ParamsBuilder::new();
+        params
+            .m_cost(self.params.m_cost())
+            .t_cost(self.params.t_cost())
+            .p_cost(self.params.p_cost());
+        let argon2 = match self.current.and_then(|id| self.pepper(&id)) {
+            Some(pepper) => Argon2::new_with_secret(
+                &pepper.secret,
+                Algorithm::Argon2id,
+                Version::V0x13,
+                params.keyid(pepper.id).build()?,
+            )?,
+            None => Argon2::new(Algorithm::Argon2id, Version::V0x13, params.build()?),
+        };
+        Ok(argon2
+            .hash_password_with_salt(password.as_bytes(), salt.as_bytes())?
+            .to_string())
+    }
+
+    /// 验证密码，哈希使用的参数和 pepper 版本从哈希本身读取
+    pub fn verify(&self, password: &str, password_hash: &str) -> ApiResult<bool> {
+        // 兼容导入的 bcrypt 哈希
+        if is_bcrypt(password_hash) {
+            return Ok(bcrypt::verify(password, password_hash)?);
+        }
+        // 解析 hash 密码
+        let parsed_hash = PasswordHash::new(password_hash)?;
+        let params = Params::try_from(&parsed_hash)?;
+        let argon2 = if params.keyid().is_empty() {
+            Argon2::default()
+        } else {
+            // 找不到对应版本的 pepper 说明配置有误，不能当作密码错误处理
+            let id = KeyId::new(params.keyid())?;
+            let pepper = self
+                .pepper(&id)
+                .ok_or(argon2::password_hash::Error::ParamInvalid { name: "keyid" })?;
+            Argon2::new_with_secret(
+                &pepper.secret,
+                Algorithm::default(),
+                Version::default(),
+                Params::default(),
+            )?
+        };
+        // 比对密码是否一样
+        Ok(argon2
+            .verify_password(password.as_bytes(), &parsed_hash)
+            .is_ok())
+    }
+
+    /// 使用一个随机密码的哈希执行一次验证，结果总是不匹配
+    ///
+    /// # 功能描述
+    /// 用户不存在时调用，使耗时与验证真实用户的密码一致，避免通过响应时间判断用户名是否存在。
+    /// 哈希在创建哈希器时使用当前的参数和 pepper 生成。
+    pub fn verify_dummy(&self, password: &str) {
+        if let Err(e) = self.verify(password, &self.dummy_hash) {
+            tracing::error!("验证占位密码哈希失败: {:?}", e);
+        }
+    }
+
+    /// 判断哈希是否需要使用当前的参数和 pepper 重新生成
+    pub fn needs_rehash(&self, password_hash: &str) -> bool {
+        if is_bcrypt(password_hash) {
+            return true;
+        }
+        let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
+            return false;
+        };
+        if parsed_hash.algorithm != argon2::ARGON2ID_IDENT
+            || parsed_hash.version != Some(Version::V0x13.into())
+        {
+            return true;
+        }
+        let Ok(params) = Params::try_from(&parsed_hash) else {
+            return true;
+        };
+        let current_id = self
+            .current
+            .as_ref()
+            .map(KeyId::as_bytes)
+            .unwrap_or_default();
+        params.m_cost() != self.params.m_cost()
+            || params.t_cost() != self.params.t_cost()
+            || params.p_cost() != self.params.p_cost()
+            || params.keyid() != current_id
+    }
+
+    fn pepper(&self, id: &KeyId) -> Option<&Pepper> {
+        self.peppers.iter().find(|pepper| &pepper.id == id)
+    }
+}
+
+/// 是否是从其他系统导入的 bcrypt 哈希
+fn is_bcrypt(password_hash: &str) -> bool {
+    ["$2a$", "$2b$", "$2x$", "$2y$"]
+        .iter()
+        .any(|prefix| password_hash.starts_with(prefix))
+}
 
 /// 使用 argon2 对密码进行加密。
 ///
@@ -18,22 +236,13 @@ use argon2::{
 ///
 /// # 示例
 /// ```
+/// use user_server::utils::crypto::encode_password;
+///
 /// let hashed_password = encode_password("123456").unwrap();
 /// println!("Hashed password: {}", hashed_password);
 /// ```
 pub fn encode_password(password: &str) -> ApiResult<String> {
-    // 生成随机 22 位 salt
-    // let salt = SaltString::try_from_rng(&mut OsRng).unwrap();
-    let salt = SaltString::generate();
-    // let salt = SaltString::from_rng(&mut OsRng);
-    // 使用默认参数生成 hash 密码
-    let argon2 = Argon2::default();
-    // 生成 hash 密码
-    let password_hash = argon2
-        .hash_password_with_salt(password.as_bytes(), salt.as_bytes())?
-        .to_string();
-    // 返回 hash 后的结果 97 位
-    Ok(password_hash)
+    password_encoder().encode(password)
 }
 
 /// 验证原始密码与加密后的密码是否匹配。
@@ -41,6 +250,7 @@ pub fn encode_password(password: &str) -> ApiResult<String> {
 /// # 功能描述
 /// 该函数接收一个原始密码和一个使用 argon2 算法加密后的密码哈希，并验证原始密码与加密后的密码哈希是否匹配。
 /// 此过程是安全的，确保了只有知道正确密码的用户才能通过验证。
+/// 从其他系统导入的 bcrypt 哈希同样可以验证，验证通过后应使用 `needs_rehash` 判断并重新哈希。
 ///
 /// # 参数
 /// - `password`: 需要验证的原始密码。必须是非空字符串。
@@ -53,19 +263,73 @@ pub fn encode_password(password: &str) -> ApiResult<String> {
 ///
 /// # 示例
 /// ```
+/// use user_server::utils::crypto::{encode_password, verify_password};
+///
 /// let hashed_password = encode_password("my_secure_password").unwrap();
 /// let is_valid = verify_password("my_secure_password", &hashed_password).unwrap();
 /// assert!(is_valid);
 /// ```
 pub fn verify_password(password: &str, password_hash: &str) -> ApiResult<bool> {
-    // 使用默认参数验证密码
-    let argon2 = Argon2::default();
-    // 解析 hash 密码
-    let parsed_hash = PasswordHash::new(password_hash)?;
-    // 比对密码是否一样
-    let verify_result = argon2
-        .verify_password(password.as_bytes(), &parsed_hash)
-        .is_ok();
-    // 返回验证的结果
-    Ok(verify_result)
+    password_encoder().verify(password, password_hash)
+}
+
+/// 用户不存在时执行一次耗时相同的密码验证。
+///
+/// # 功能描述
+/// 登录时用户不存在也要调用，使响应时间与密码错误时一致，避免通过响应时间枚举用户名。
+///
+/// # 参数
+/// - `password`: 用户提交的密码
+pub fn verify_dummy_password(password: &str) {
+    password_encoder().verify_dummy(password)
+}
+
+/// 判断密码哈希是否需要使用当前的参数和 pepper 重新生成。
+///
+/// # 功能描述
+/// bcrypt 等旧算法、非 Argon2id 的哈希，内存开销、迭代次数、并行度与当前配置不一致的哈希，
+/// 以及 pepper 版本不是当前版本的哈希都需要重新生成。只应在密码验证通过后调用，此时才拿得到原始密码。
+///
+/// # 参数
+/// - `password_hash`: 数据库中保存的密码哈希
+///
+/// # 返回值
+/// 需要重新哈希时返回 `true`
+///
+/// # 示例
+/// ```
+/// use user_server::utils::crypto::{encode_password, needs_rehash};
+///
+/// let hashed_password = encode_password("my_secure_password").unwrap();
+/// assert!(!needs_rehash(&hashed_password));
+/// ```
+pub fn needs_rehash(password_hash: &str) -> bool {
+    password_encoder().needs_rehash(password_hash)
+}
+
+/// 生成一个随机的不透明令牌（如 refresh token）。
+///
+/// # 功能描述
+/// 使用操作系统随机数生成 32 字节随机数，并编码为 64 位十六进制字符串。
+///
+/// # 返回值
+/// 返回生成的令牌字符串。
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// 计算令牌的 SHA-256 摘要。
+///
+/// # 功能描述
+/// 令牌本身已具有足够的随机性，因此只需存储其 SHA-256 摘要，数据库泄露时也无法还原出原始令牌。
+///
+/// # 参数
+/// - `token`: 原始令牌字符串。
+///
+/// # 返回值
+/// 返回 64 位十六进制的摘要字符串。
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
 }
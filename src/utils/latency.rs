@@ -1,9 +1,12 @@
 use std::time::Duration;
 
 use axum::http::Response;
+use tonic::Code;
 use tower_http::trace::OnResponse;
 use tracing::Span;
 
+use crate::middlewares::metrics::grpc_metrics::response_code;
+
 /// Latency on response 响应延时
 #[derive(Debug, Clone, Copy)]
 pub struct LatencyOnResponse;
@@ -18,6 +21,42 @@ impl<B> OnResponse<B> for LatencyOnResponse {
     }
 }
 
+/// gRPC 调用完成时记录用时和状态码
+///
+/// # 功能描述
+/// 与 HTTP 的 `LatencyOnResponse` 输出相同格式的日志，并把 gRPC 状态码记录到 span 的 grpc_code 上。
+/// 服务端错误（如 `Internal`、`Unavailable`）同时把 span 标记为失败，在链路追踪中可以直接筛选。
+#[derive(Debug, Clone, Copy)]
+pub struct GrpcLatencyOnResponse;
+
+impl<B> OnResponse<B> for GrpcLatencyOnResponse {
+    fn on_response(self, response: &Response<B>, latency: Duration, span: &Span) {
+        let code = response_code(response.headers());
+        span.record("grpc_code", tracing::field::debug(code));
+        if is_server_error(code) {
+            span.record("otel.status_code", "error");
+        }
+        tracing::info!(
+            latency = %Latency(latency),
+            grpc_code = ?code,
+            "Finished processing request"
+        )
+    }
+}
+
+/// 是否为服务端的错误，调用方导致的错误（如参数错误、未登录）不算
+fn is_server_error(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unknown
+            | Code::DeadlineExceeded
+            | Code::Unimplemented
+            | Code::Internal
+            | Code::Unavailable
+            | Code::DataLoss
+    )
+}
+
 /// Latency 延迟用时
 struct Latency(Duration);
 
@@ -1,3 +1,4 @@
 pub mod crypto;
 pub mod latency;
 pub mod timezone;
+pub mod trace;
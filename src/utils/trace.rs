@@ -0,0 +1,140 @@
+use opentelemetry::{
+    Context, global,
+    propagation::{Extractor, Injector},
+    trace::TraceContextExt,
+};
+use tonic::transport::server::TcpConnectInfo;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::middlewares::{
+    metrics::grpc_metrics::split_grpc_path,
+    request_id::http_request_id::{REQUEST_ID_KEY, current_request_id, request_id_from_headers},
+};
+
+/// 从 HTTP 头读取链路上下文
+struct HeaderExtractor<'a>(&'a http::HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// 把链路上下文写入 gRPC metadata
+struct MetadataInjector<'a>(&'a mut tonic::metadata::MetadataMap);
+
+impl Injector for MetadataInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (
+            tonic::metadata::MetadataKey::from_bytes(key.as_bytes()),
+            value.parse(),
+        ) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
+/// 从请求头的 `traceparent` 读取上游的链路上下文，没有时返回空的上下文
+pub fn extract_context(headers: &http::HeaderMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+/// 把当前 span 的链路上下文以 `traceparent` 写入 gRPC metadata
+pub fn inject_context(metadata: &mut tonic::metadata::MetadataMap) {
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut MetadataInjector(metadata))
+    });
+}
+
+/// gRPC 客户端拦截器，把当前请求的链路上下文和 `x-request-id` 传给 gRPC 服务
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceContextInterceptor;
+
+impl tonic::service::Interceptor for TraceContextInterceptor {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> Result<tonic::Request<()>, tonic::Status> {
+        inject_context(request.metadata_mut());
+        if let Some(value) = current_request_id().and_then(|id| id.parse().ok()) {
+            request.metadata_mut().insert(REQUEST_ID_KEY, value);
+        }
+        Ok(request)
+    }
+}
+
+/// 为 HTTP 请求创建 span
+///
+/// # 功能描述
+/// 请求头带有 `traceparent` 时作为上游的子 span，否则开始新的链路。
+/// trace_id 和 request_id 记录在 span 上，日志中可以按它们关联 HTTP 和 gRPC 服务的日志。
+pub fn make_http_span<B>(request: &http::Request<B>) -> Span {
+    let method = request.method();
+    let path = request.uri().path();
+    let request_id = request_id_from_headers(request.headers());
+    let span = tracing::info_span!(
+        "Api Request",
+        request_id = %request_id,
+        method = %method,
+        path = %path,
+        trace_id = tracing::field::Empty,
+        otel.name = %format!("{method} {path}"),
+        otel.kind = "server",
+    );
+    attach_parent(&span, request.headers());
+    span
+}
+
+/// 为 gRPC 调用创建 span
+///
+/// # 功能描述
+/// 从 metadata 的 `traceparent` 继续 HTTP 服务传来的链路，span 名称为 `user.UserService/UserLogin` 这样的完整方法名。
+/// request_id 使用 HTTP 服务传来的 `x-request-id`，直接调用 gRPC 服务时生成一个新的。
+/// peer 为调用方的地址，grpc_code 在调用完成后由 `GrpcLatencyOnResponse` 记录。
+pub fn make_grpc_span<B>(request: &http::Request<B>) -> Span {
+    let path = request.uri().path();
+    let (service, method) = split_grpc_path(path);
+    let request_id = request_id_from_headers(request.headers());
+    let span = tracing::info_span!(
+        "gRPC Request",
+        path = %path,
+        peer = tracing::field::Empty,
+        request_id = %request_id,
+        grpc_code = tracing::field::Empty,
+        trace_id = tracing::field::Empty,
+        otel.status_code = tracing::field::Empty,
+        otel.name = %path.trim_start_matches('/'),
+        otel.kind = "server",
+        rpc.system = "grpc",
+        rpc.service = %service,
+        rpc.method = %method,
+    );
+    if let Some(peer) = request
+        .extensions()
+        .get::<TcpConnectInfo>()
+        .and_then(TcpConnectInfo::remote_addr)
+    {
+        span.record("peer", tracing::field::display(peer));
+    }
+    attach_parent(&span, request.headers());
+    span
+}
+
+/// 设置上游的链路上下文，并把 trace_id 记录到 span 上
+fn attach_parent(span: &Span, headers: &http::HeaderMap) {
+    let parent = extract_context(headers);
+    if parent.span().span_context().is_valid() {
+        // 没有安装 OpenTelemetry 层时会失败，此时不需要链路上下文
+        let _ = span.set_parent(parent);
+    }
+    let span_context = span.context().span().span_context().clone();
+    if span_context.is_valid() {
+        span.record("trace_id", tracing::field::display(span_context.trace_id()));
+    }
+}
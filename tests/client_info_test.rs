@@ -0,0 +1,103 @@
+use user_server::{
+    common::client_info::{ClientInfo, TrustedProxies},
+    service_impl::{login_history::LoginOutcome, password_reset::ip_limit_key},
+};
+
+#[test]
+fn test_client_info_round_trip_through_metadata() {
+    let client = ClientInfo {
+        ip: Some("203.0.113.7".parse().unwrap()),
+        user_agent: Some("Mozilla/5.0 (X11; Linux x86_64)".to_string()),
+    };
+    let mut request = tonic::Request::new(());
+    client.forward(&mut request);
+    // 没有连接地址时无法确认调用方是可信代理，不使用转发的 IP
+    let forwarded = ClientInfo::from_grpc(&request);
+    assert!(forwarded.ip.is_none());
+    assert_eq!(
+        forwarded.user_agent.as_deref(),
+        Some("Mozilla/5.0 (X11; Linux x86_64)")
+    );
+    // 没有转发信息且没有连接地址时为空
+    let empty = ClientInfo::from_grpc(&tonic::Request::new(()));
+    assert!(empty.ip.is_none() && empty.user_agent.is_none());
+}
+
+#[test]
+fn test_login_outcome_reason() {
+    assert_eq!(LoginOutcome::Success.reason(), None);
+    assert_eq!(LoginOutcome::BadPassword.reason(), Some("bad_password"));
+    assert_eq!(LoginOutcome::Locked.reason(), Some("locked"));
+}
+
+fn request_from(peer: &str, forwarded: Option<&str>) -> tonic::Request<()> {
+    let mut request = tonic::Request::new(());
+    request
+        .extensions_mut()
+        .insert(tonic::transport::server::TcpConnectInfo {
+            local_addr: None,
+            remote_addr: Some(peer.parse().unwrap()),
+        });
+    if let Some(forwarded) = forwarded {
+        request
+            .metadata_mut()
+            .insert("x-forwarded-for", forwarded.parse().unwrap());
+    }
+    request
+}
+
+#[test]
+fn test_forwarded_ip_from_untrusted_peer_is_ignored() {
+    let trusted = TrustedProxies::parse(&["172.18.0.0/16"]).unwrap();
+    let spoofed = request_from("198.51.100.9:40000", Some("203.0.113.7"));
+    let client = ClientInfo::from_grpc_with(&spoofed, &trusted);
+    assert_eq!(client.ip, Some("198.51.100.9".parse().unwrap()));
+    // 没有配置可信代理时同样忽略
+    let client = ClientInfo::from_grpc_with(&spoofed, &TrustedProxies::default());
+    assert_eq!(client.ip, Some("198.51.100.9".parse().unwrap()));
+}
+
+#[test]
+fn test_forwarded_ip_from_trusted_peer_is_parsed() {
+    let trusted = TrustedProxies::parse(&["172.18.0.0/16", "::1"]).unwrap();
+    let request = request_from("172.18.0.3:40000", Some(" 203.0.113.7, 10.0.0.1"));
+    let client = ClientInfo::from_grpc_with(&request, &trusted);
+    assert_eq!(client.ip, Some("203.0.113.7".parse().unwrap()));
+    // 无法解析的值退回到对端地址
+    let request = request_from("[::1]:40000", Some(&"9".repeat(60)));
+    let client = ClientInfo::from_grpc_with(&request, &trusted);
+    assert_eq!(client.ip, Some("::1".parse().unwrap()));
+}
+
+#[test]
+fn test_trusted_proxies_parse() {
+    let trusted = TrustedProxies::parse(&["10.0.0.0/8", "2001:db8::/32"]).unwrap();
+    assert!(trusted.contains("10.200.1.1".parse().unwrap()));
+    assert!(!trusted.contains("11.0.0.1".parse().unwrap()));
+    assert!(trusted.contains("2001:db8::1".parse().unwrap()));
+    assert!(trusted.contains("::ffff:10.0.0.1".parse().unwrap()));
+    assert!(TrustedProxies::parse(&["10.0.0.0/33"]).is_err());
+    assert!(TrustedProxies::parse(&["example.com"]).is_err());
+}
+
+#[test]
+fn test_forwarded_ips_use_separate_reset_buckets() {
+    // HTTP 网关转发的不同客户端按各自的 IP 计数，而不是共用网关的 IP
+    let trusted = TrustedProxies::parse(&["172.18.0.0/16"]).unwrap();
+    let keys: Vec<String> = ["203.0.113.7", "198.51.100.9"]
+        .into_iter()
+        .map(|ip| {
+            let client = ClientInfo {
+                ip: Some(ip.parse().unwrap()),
+                user_agent: None,
+            };
+            let mut request = request_from("172.18.0.3:40000", None);
+            client.forward(&mut request);
+            let forwarded = ClientInfo::from_grpc_with(&request, &trusted);
+            assert_eq!(forwarded.ip, client.ip);
+            ip_limit_key(forwarded.ip.unwrap())
+        })
+        .collect();
+    assert_ne!(keys[0], keys[1]);
+    assert!(!keys.contains(&ip_limit_key("172.18.0.3".parse().unwrap())));
+}
@@ -0,0 +1,89 @@
+//! 需要 PostgreSQL 的集成测试共用的工具
+//!
+//! 设置 `TEST_DATABASE_URL` 后，每个测试在独立的 schema 中执行 `migrations` 目录下的迁移，
+//! 需要 Redis 的测试另外读取 `TEST_REDIS_URL`，未设置时跳过，CI 中没有数据库也能运行其他测试。
+
+#![allow(dead_code)]
+
+use sqlx::{
+    Connection, Executor, PgPool,
+    migrate::Migrator,
+    postgres::{PgConnection, PgPoolOptions},
+};
+
+/// 测试数据库的连接地址
+const DATABASE_URL_ENV: &str = "TEST_DATABASE_URL";
+/// 测试 Redis 的连接地址
+const REDIS_URL_ENV: &str = "TEST_REDIS_URL";
+
+/// 创建测试使用的连接池
+///
+/// # 功能描述
+/// 新建一个随机命名的 schema，连接池的 `search_path` 指向该 schema，并执行全部迁移，
+/// 测试之间互不影响。
+///
+/// # 返回值
+/// 未设置 `TEST_DATABASE_URL` 时返回 `None`
+pub async fn test_pool() -> Option<PgPool> {
+    let Ok(url) = std::env::var(DATABASE_URL_ENV) else {
+        eprintln!("未设置 {DATABASE_URL_ENV}，跳过数据库测试");
+        return None;
+    };
+    let schema = format!("test_{}", xid::new());
+    let mut conn = PgConnection::connect(&url).await.unwrap();
+    conn.execute(format!(r#"CREATE SCHEMA "{schema}""#).as_str())
+        .await
+        .unwrap();
+    conn.close().await.unwrap();
+
+    let search_path = format!(r#"SET search_path TO "{schema}""#);
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .after_connect(move |conn, _| {
+            let search_path = search_path.clone();
+            Box::pin(async move {
+                conn.execute(search_path.as_str()).await?;
+                Ok(())
+            })
+        })
+        .connect(&url)
+        .await
+        .unwrap();
+    Migrator::new(std::path::Path::new("migrations"))
+        .await
+        .unwrap()
+        .run(&pool)
+        .await
+        .unwrap();
+    Some(pool)
+}
+
+/// 插入一个测试用户，返回用户 id
+pub async fn insert_user(pool: &PgPool, username: &str) -> i32 {
+    sqlx::query_scalar(r#"INSERT INTO "user" (username, password) VALUES ($1, '') RETURNING id"#)
+        .bind(username)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+/// 初始化全局的 Redis 连接池
+///
+/// # 功能描述
+/// 每个 `#[tokio::test]` 使用各自的运行时，连接池不保留空闲连接，避免复用已经结束的运行时中的连接。
+///
+/// # 返回值
+/// 未设置 `TEST_REDIS_URL` 时返回 `false`
+pub async fn init_test_redis() -> bool {
+    let Ok(url) = std::env::var(REDIS_URL_ENV) else {
+        eprintln!("未设置 {REDIS_URL_ENV}，跳过 Redis 测试");
+        return false;
+    };
+    let client = mobc_redis::redis::Client::open(url).unwrap();
+    let pool = mobc::Pool::builder()
+        .max_idle(0)
+        .build(mobc_redis::RedisConnectionManager::new(client));
+    // 同一个测试文件中只有第一次设置生效
+    let _ = user_server::db::set_global_redis(pool).await;
+    true
+}
@@ -0,0 +1,137 @@
+use argon2::{Algorithm, Argon2, Params, PasswordHasher, Version};
+use user_server::{
+    conf::password_hash::PasswordHashConfig,
+    utils::crypto::{
+        PasswordEncoder, encode_password, generate_token, hash_token, needs_rehash,
+        verify_dummy_password, verify_password,
+    },
+};
+
+#[test]
+fn test_password_round_trip() {
+    let hashed_password = encode_password("my_secure_password").unwrap();
+    assert!(verify_password("my_secure_password", &hashed_password).unwrap());
+    assert!(!verify_password("wrong_password", &hashed_password).unwrap());
+}
+
+#[test]
+fn test_generate_token_is_random() {
+    let first = generate_token();
+    let second = generate_token();
+    assert_eq!(first.len(), 64);
+    assert_ne!(first, second);
+}
+
+#[test]
+fn test_hash_token_is_stable() {
+    let token = generate_token();
+    assert_eq!(hash_token(&token), hash_token(&token));
+    assert_ne!(hash_token(&token), token);
+    assert_eq!(hash_token(&token).len(), 64);
+}
+
+/// 使用指定的算法和参数生成 Argon2 哈希
+fn argon2_hash(password: &str, algorithm: Algorithm, params: Params) -> String {
+    Argon2::new(algorithm, Version::V0x13, params)
+        .hash_password_with_salt(password.as_bytes(), b"fixed-salt-for-tests")
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_weaker_or_legacy_hashes_need_rehash() {
+    let hashed_password = encode_password("my_secure_password").unwrap();
+    assert!(!needs_rehash(&hashed_password));
+    // 参数比当前配置弱
+    let weak = argon2_hash(
+        "my_secure_password",
+        Algorithm::Argon2id,
+        Params::new(8, 1, 1, None).unwrap(),
+    );
+    assert!(verify_password("my_secure_password", &weak).unwrap());
+    assert!(needs_rehash(&weak));
+    // 不是 Argon2id
+    let argon2i = argon2_hash("my_secure_password", Algorithm::Argon2i, Params::default());
+    assert!(verify_password("my_secure_password", &argon2i).unwrap());
+    assert!(needs_rehash(&argon2i));
+}
+
+#[test]
+fn test_imported_bcrypt_hash_verifies_and_needs_rehash() {
+    let bcrypt_hash = bcrypt::hash("legacy_password", 4).unwrap();
+    assert!(verify_password("legacy_password", &bcrypt_hash).unwrap());
+    assert!(!verify_password("wrong_password", &bcrypt_hash).unwrap());
+    assert!(needs_rehash(&bcrypt_hash));
+}
+
+#[test]
+fn test_password_hash_config_validation() {
+    assert!(PasswordHashConfig::default().params().is_ok());
+    let config: PasswordHashConfig =
+        serde_json::from_value(serde_json::json!({"memory_kib": 4, "parallelism": 1})).unwrap();
+    assert!(config.params().is_err());
+}
+
+/// 使用 json 构造带 pepper 的哈希器，默认参数调低以加快测试
+fn encoder(value: serde_json::Value) -> PasswordEncoder {
+    let mut config = serde_json::json!({"memory_kib": 64, "iterations": 1});
+    config
+        .as_object_mut()
+        .unwrap()
+        .extend(value.as_object().unwrap().clone());
+    PasswordEncoder::new(&serde_json::from_value(config).unwrap()).unwrap()
+}
+
+#[test]
+fn test_pepper_rotation_keeps_old_hashes_valid() {
+    let plain = encoder(serde_json::json!({}));
+    let v1 = encoder(serde_json::json!({"pepper": {"id": "v1", "secret": "pepper-one"}}));
+    let v2 = encoder(serde_json::json!({
+        "pepper": {"id": "v2", "secret": "pepper-two"},
+        "previous_peppers": [{"id": "v1", "secret": "pepper-one"}],
+    }));
+    let plain_hash = plain.encode("my_secure_password").unwrap();
+    let v1_hash = v1.encode("my_secure_password").unwrap();
+    let v2_hash = v2.encode("my_secure_password").unwrap();
+    assert!(!plain_hash.contains("keyid="));
+    assert!(v1_hash.contains("keyid="));
+
+    // 轮换后新旧版本的哈希都可以验证，只有当前版本的哈希不需要重新生成
+    for hash in [&plain_hash, &v1_hash, &v2_hash] {
+        assert!(v2.verify("my_secure_password", hash).unwrap());
+        assert!(!v2.verify("wrong_password", hash).unwrap());
+    }
+    assert!(v2.needs_rehash(&plain_hash));
+    assert!(v2.needs_rehash(&v1_hash));
+    assert!(!v2.needs_rehash(&v2_hash));
+
+    // 没有 pepper 的数据库哈希无法被验证
+    assert!(plain.verify("my_secure_password", &v1_hash).is_err());
+    let forged = encoder(serde_json::json!({"pepper": {"id": "v1", "secret": "guessed"}}));
+    assert!(!forged.verify("my_secure_password", &v1_hash).unwrap());
+}
+
+#[test]
+fn test_invalid_pepper_config_is_rejected() {
+    let config =
+        |value: serde_json::Value| -> PasswordHashConfig { serde_json::from_value(value).unwrap() };
+    let too_long = config(serde_json::json!({"pepper": {"id": "version-10", "secret": "x"}}));
+    assert!(PasswordEncoder::new(&too_long).is_err());
+    let duplicated = config(serde_json::json!({
+        "pepper": {"id": "v1", "secret": "x"},
+        "previous_peppers": [{"id": "v1", "secret": "y"}],
+    }));
+    assert!(PasswordEncoder::new(&duplicated).is_err());
+    let missing =
+        config(serde_json::json!({"pepper": {"id": "v1", "secret": "file:/path/does/not/exist"}}));
+    assert!(PasswordEncoder::new(&missing).is_err());
+}
+
+#[test]
+fn test_verify_dummy_uses_current_pepper() {
+    // 占位哈希与真实哈希使用同样的参数和 pepper，验证时不会报错
+    let peppered = encoder(serde_json::json!({"pepper": {"id": "v1", "secret": "pepper-one"}}));
+    peppered.verify_dummy("any-password");
+    peppered.verify_dummy("another-password");
+    verify_dummy_password("any-password");
+}
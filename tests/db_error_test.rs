@@ -0,0 +1,41 @@
+use tonic::Code;
+use user_server::db::error::{classify, classify_sqlstate, db_status};
+
+#[test]
+fn test_classify_sqlstate() {
+    assert_eq!(classify_sqlstate("23505"), Code::AlreadyExists);
+    assert_eq!(classify_sqlstate("23503"), Code::FailedPrecondition);
+    assert_eq!(classify_sqlstate("23502"), Code::InvalidArgument);
+    assert_eq!(classify_sqlstate("23514"), Code::InvalidArgument);
+    assert_eq!(classify_sqlstate("22001"), Code::InvalidArgument);
+    assert_eq!(classify_sqlstate("40001"), Code::Aborted);
+    assert_eq!(classify_sqlstate("40P01"), Code::Aborted);
+    assert_eq!(classify_sqlstate("57014"), Code::DeadlineExceeded);
+    assert_eq!(classify_sqlstate("08006"), Code::Unavailable);
+    assert_eq!(classify_sqlstate("53300"), Code::Unavailable);
+    assert_eq!(classify_sqlstate("57P01"), Code::Unavailable);
+    assert_eq!(classify_sqlstate("42P01"), Code::Internal);
+}
+
+#[test]
+fn test_classify_driver_errors() {
+    assert_eq!(classify(&sqlx::Error::PoolTimedOut), Code::Unavailable);
+    assert_eq!(classify(&sqlx::Error::PoolClosed), Code::Unavailable);
+    assert_eq!(classify(&sqlx::Error::RowNotFound), Code::NotFound);
+    assert_eq!(
+        classify(&sqlx::Error::Protocol("unexpected message".into())),
+        Code::Internal
+    );
+}
+
+#[test]
+fn test_db_status_hides_details() {
+    let status = db_status(
+        "查询用户失败",
+        sqlx::Error::Protocol("SELECT password FROM \"user\"".into()),
+    );
+    assert_eq!(status.code(), Code::Internal);
+    assert!(!status.message().contains("SELECT"));
+    let status = db_status("查询用户失败", sqlx::Error::PoolTimedOut);
+    assert_eq!(status.code(), Code::Unavailable);
+}
@@ -0,0 +1,112 @@
+use axum::{body::to_bytes, http::StatusCode, response::IntoResponse};
+use tonic::{Code, Status};
+use user_server::response::{error_code::ErrorCode, errors::ApiError};
+
+/// 把错误转换成响应，返回 HTTP 状态码和响应体
+async fn respond(error: ApiError) -> (StatusCode, serde_json::Value) {
+    let response = error.into_response();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[test]
+fn test_error_code_round_trip() {
+    let status = ErrorCode::UserAlreadyExists.status(Code::AlreadyExists, "要注册的帐号已经存在！");
+    assert_eq!(
+        ErrorCode::from_status(&status),
+        ErrorCode::UserAlreadyExists
+    );
+    assert_eq!(
+        ErrorCode::parse("USER_ALREADY_EXISTS"),
+        Some(ErrorCode::UserAlreadyExists)
+    );
+    assert_eq!(ErrorCode::parse("NO_SUCH_CODE"), None);
+    assert_eq!(
+        serde_json::to_value(ErrorCode::InvalidCredentials).unwrap(),
+        "INVALID_CREDENTIALS"
+    );
+    // 没有携带错误码时按 gRPC 状态码推断
+    assert_eq!(
+        ErrorCode::from_status(&Status::not_found("用户不存在！")),
+        ErrorCode::NotFound
+    );
+}
+
+#[test]
+fn test_error_code_strings_match_serde() {
+    // 字符串形式、解析和序列化必须一致，新增的错误码也会自动覆盖
+    for code in ErrorCode::ALL {
+        assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+        assert_eq!(code.to_string(), code.as_str());
+        assert_eq!(ErrorCode::parse(code.as_str()), Some(*code));
+    }
+}
+
+#[tokio::test]
+async fn test_grpc_error_uses_catalogue() {
+    let status = ErrorCode::InvalidCredentials.status(Code::Unauthenticated, "帐号或密码不正确！");
+    let (http_status, body) = respond(status.into()).await;
+    assert_eq!(http_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "INVALID_CREDENTIALS");
+    assert_eq!(body["message"], "帐号或密码不正确！");
+
+    let (http_status, body) = respond(Status::already_exists("记录已存在！").into()).await;
+    assert_eq!(http_status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "ALREADY_EXISTS");
+
+    let (http_status, body) = respond(Status::unavailable("数据库暂时不可用").into()).await;
+    assert_eq!(http_status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"], "SERVICE_UNAVAILABLE");
+}
+
+#[tokio::test]
+async fn test_internal_errors_hide_details() {
+    let (http_status, body) =
+        respond(Status::internal("Failed to encode JWT: bad key").into()).await;
+    assert_eq!(http_status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["error"], "INTERNAL_ERROR");
+    assert!(!body["message"].as_str().unwrap().contains("JWT"));
+
+    let (http_status, body) = respond(ApiError::DatabaseError(sqlx::Error::PoolTimedOut)).await;
+    assert_eq!(http_status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!body["message"].as_str().unwrap().contains("pool"));
+}
+
+#[tokio::test]
+async fn test_local_errors_have_correct_status() {
+    let (http_status, body) = respond(ApiError::Biz("操作失败".to_string())).await;
+    assert_eq!(http_status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "BUSINESS_ERROR");
+    assert_eq!(body["code"], -1);
+
+    let (http_status, body) = respond(ApiError::InternalServerError).await;
+    assert_eq!(http_status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["error"], "INTERNAL_ERROR");
+
+    let (http_status, body) = respond(ApiError::Unauthenticated("没有登陆".to_string())).await;
+    assert_eq!(http_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "UNAUTHENTICATED");
+}
+
+#[tokio::test]
+async fn test_validation_errors_carry_fields() {
+    use validator::Validate;
+    let param: user_server::handlers::common::model::RegisterUserParam =
+        serde_json::from_value(serde_json::json!({
+            "username": "a",
+            "password": "",
+        }))
+        .unwrap();
+    let errors = param.validate().unwrap_err();
+    let error = ApiError::from(axum_valid::ValidationRejection::Valid(errors));
+    let (http_status, body) = respond(error).await;
+    assert_eq!(http_status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "VALIDATION_FAILED");
+    let fields = body["data"].as_array().unwrap();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0]["field"], "password");
+    assert_eq!(fields[0]["reason"], "LENGTH");
+    assert_eq!(fields[1]["field"], "username");
+    assert_eq!(fields[1]["message"], "用户名长度必须在 2-20 之间");
+}
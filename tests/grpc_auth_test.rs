@@ -0,0 +1,45 @@
+use std::convert::Infallible;
+
+use tower::{Layer, ServiceExt, service_fn};
+use user_server::middlewares::auth::{grpc_auth::GrpcAuthLayer, jwt::JWT};
+
+static JWT_FOR_TEST: std::sync::LazyLock<JWT> = std::sync::LazyLock::new(JWT::default);
+
+fn grpc_status(response: &http::Response<String>) -> Option<&str> {
+    response
+        .headers()
+        .get("grpc-status")
+        .and_then(|value| value.to_str().ok())
+}
+
+#[tokio::test]
+async fn test_public_method_skips_authentication() {
+    let layer =
+        GrpcAuthLayer::new(&JWT_FOR_TEST).with_public_methods("user.UserService", ["UserLogin"]);
+    let service = layer.layer(service_fn(|_request: http::Request<()>| async {
+        Ok::<_, Infallible>(http::Response::new(String::from("ok")))
+    }));
+    let request = http::Request::builder()
+        .uri("/user.UserService/UserLogin")
+        .body(())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.body(), "ok");
+    assert_eq!(grpc_status(&response), None);
+}
+
+#[tokio::test]
+async fn test_protected_method_requires_token() {
+    let layer =
+        GrpcAuthLayer::new(&JWT_FOR_TEST).with_public_methods("user.UserService", ["UserLogin"]);
+    let service = layer.layer(service_fn(|_request: http::Request<()>| async {
+        Ok::<_, Infallible>(http::Response::new(String::from("ok")))
+    }));
+    let request = http::Request::builder()
+        .uri("/user.UserService/GetUser")
+        .body(())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    // 16 = Unauthenticated
+    assert_eq!(grpc_status(&response), Some("16"));
+}
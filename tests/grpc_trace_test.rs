@@ -0,0 +1,85 @@
+use std::{
+    io::Write,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::body::Body;
+use tonic::transport::server::TcpConnectInfo;
+use tower::{ServiceBuilder, ServiceExt};
+use tower_http::trace::TraceLayer;
+use user_server::utils::{latency::GrpcLatencyOnResponse, trace::make_grpc_span};
+
+/// 把日志写入内存，便于检查输出
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 经过 gRPC 追踪层调用一次，返回输出的日志
+async fn call(grpc_status: Option<&'static str>) -> String {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    let service = ServiceBuilder::new()
+        .layer(
+            TraceLayer::new_for_grpc()
+                .make_span_with(make_grpc_span::<Body>)
+                .on_request(())
+                .on_response(GrpcLatencyOnResponse)
+                .on_failure(()),
+        )
+        .service_fn(move |_request: http::Request<Body>| async move {
+            let mut response = http::Response::builder().header("content-type", "application/grpc");
+            if let Some(grpc_status) = grpc_status {
+                response = response.header("grpc-status", grpc_status);
+            }
+            Ok::<_, std::convert::Infallible>(response.body(Body::empty()).unwrap())
+        });
+    let mut request = http::Request::post("/user.UserService/UserLogin")
+        .header("x-request-id", "req-1")
+        .body(Body::empty())
+        .unwrap();
+    request.extensions_mut().insert(TcpConnectInfo {
+        local_addr: None,
+        remote_addr: Some("127.0.0.1:5555".parse::<SocketAddr>().unwrap()),
+    });
+    service.oneshot(request).await.unwrap();
+
+    let output = captured.0.lock().unwrap().clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[tokio::test]
+async fn test_grpc_call_logs_code_peer_and_request_id() {
+    let output = call(Some("5")).await;
+    assert!(output.contains("Finished processing request"), "{output}");
+    assert!(output.contains("grpc_code=NotFound"), "{output}");
+    assert!(output.contains("peer=127.0.0.1:5555"), "{output}");
+    assert!(output.contains("request_id=req-1"), "{output}");
+    assert!(
+        output.contains("path=/user.UserService/UserLogin"),
+        "{output}"
+    );
+    assert!(output.contains(" ms") || output.contains(" μs"), "{output}");
+}
+
+#[tokio::test]
+async fn test_grpc_call_without_status_is_ok() {
+    let output = call(None).await;
+    assert!(output.contains("grpc_code=Ok"), "{output}");
+}
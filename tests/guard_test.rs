@@ -0,0 +1,32 @@
+use user_server::middlewares::auth::{
+    guard::require_grpc_identity, identity::Identity, principal::Principal,
+};
+
+fn principal(identity: Identity) -> Principal {
+    Principal {
+        id: 1,
+        username: "tester".to_string(),
+        identity,
+    }
+}
+
+#[test]
+fn test_identity_order() {
+    assert!(Identity::Guest < Identity::Member);
+    assert!(Identity::Member < Identity::Vip);
+    assert!(Identity::Vip < Identity::Admin);
+    assert!(principal(Identity::Admin).has_identity(&Identity::Vip));
+    assert!(!principal(Identity::Member).has_identity(&Identity::Admin));
+}
+
+#[test]
+fn test_grpc_identity_guard() {
+    let mut request = tonic::Request::new(());
+    let status = require_grpc_identity(&request, &Identity::Member).unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+    request.extensions_mut().insert(principal(Identity::Vip));
+    assert!(require_grpc_identity(&request, &Identity::Member).is_ok());
+    let status = require_grpc_identity(&request, &Identity::Admin).unwrap_err();
+    assert_eq!(status.code(), tonic::Code::PermissionDenied);
+}
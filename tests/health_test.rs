@@ -0,0 +1,15 @@
+use axum::{body::Body, http::Request};
+use tower::ServiceExt;
+use user_server::{router::health::get_health_router, state::app_state::AppState};
+
+#[tokio::test]
+async fn test_liveness_does_not_need_dependencies() {
+    // 连接是惰性建立的，gRPC 服务不存在时存活检查同样返回 200
+    let state = AppState::new("http://[::1]:1").await.unwrap();
+    let app = get_health_router().with_state(state);
+    let response = app
+        .oneshot(Request::get("/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+}
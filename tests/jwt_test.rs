@@ -0,0 +1,192 @@
+use user_server::{
+    conf::{
+        app::AppConfig,
+        jwt::{JwtConfig, SigningKeyConfig},
+        secret::resolve_secret,
+    },
+    middlewares::auth::{
+        identity::Identity,
+        jwt::{JWT, init_global_jwt},
+        principal::Principal,
+    },
+};
+
+#[test]
+fn test_encode_and_decode() {
+    let jwt = JWT::new(&JwtConfig::default()).unwrap();
+    let token = jwt
+        .encode(Principal {
+            id: 7,
+            username: "tester".to_string(),
+            identity: Identity::Vip,
+        })
+        .unwrap();
+    let claims = jwt.decode_claims(&token).unwrap();
+    assert!(claims.exp() > claims.iat());
+    let principal = claims.principal().unwrap();
+    assert_eq!(principal.id, 7);
+    assert_eq!(principal.username, "tester");
+    assert_eq!(principal.identity, Identity::Vip);
+    assert!(jwt.decode("not a token").is_err());
+}
+
+#[test]
+fn test_resolve_secret_from_file() {
+    let path = std::env::temp_dir().join(format!("jwt_secret_{}", std::process::id()));
+    std::fs::write(&path, "file-secret\n").unwrap();
+    let secret = resolve_secret(&format!("file:{}", path.display())).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(secret, "file-secret");
+    assert_eq!(resolve_secret("inline-secret").unwrap(), "inline-secret");
+    assert!(resolve_secret("file:/path/does/not/exist").is_err());
+}
+
+/// 使用 tests/fixtures 中的测试密钥构造配置
+fn asymmetric_config(algorithm: &str, prefix: &str, with_private_key: bool) -> JwtConfig {
+    let fixture = |name: &str| {
+        format!(
+            "file:{}/tests/fixtures/{prefix}_{name}.pem",
+            env!("CARGO_MANIFEST_DIR")
+        )
+    };
+    let mut config = serde_json::json!({
+        "algorithm": algorithm,
+        "public_key": fixture("public"),
+    });
+    if with_private_key {
+        config["private_key"] = fixture("private").into();
+    }
+    serde_json::from_value(config).unwrap()
+}
+
+#[test]
+fn test_asymmetric_algorithms_publish_jwks() {
+    let principal = Principal {
+        id: 9,
+        username: "remote".to_string(),
+        identity: Identity::Member,
+    };
+    for (algorithm, prefix) in [("RS256", "rsa"), ("ES256", "ec"), ("EdDSA", "ed")] {
+        let jwt = JWT::new(&asymmetric_config(algorithm, prefix, true)).unwrap();
+        let token = jwt.encode(principal.clone()).unwrap();
+        assert_eq!(jwt.decode(&token).unwrap().id, 9);
+        // kid 写入 header，并与公布的 JWK 一致
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        let jwks = jwt.jwks();
+        assert_eq!(jwks.keys.len(), 1);
+        let jwk = jwks.find(header.kid.as_deref().unwrap()).unwrap();
+        // 其他服务只凭 JWK 就能校验 token
+        let key = jsonwebtoken::DecodingKey::from_jwk(jwk).unwrap();
+        let mut validation = jsonwebtoken::Validation::new(header.alg);
+        validation.set_audience(&["audience"]);
+        jsonwebtoken::decode::<serde_json::Value>(&token, &key, &validation).unwrap();
+        // 只有公钥时只能校验，不能签发
+        let verifier = JWT::new(&asymmetric_config(algorithm, prefix, false)).unwrap();
+        assert_eq!(verifier.decode(&token).unwrap().username, "remote");
+        assert!(verifier.encode(principal.clone()).is_err());
+    }
+    assert!(JWT::default().jwks().keys.is_empty());
+}
+
+#[test]
+fn test_asymmetric_algorithm_requires_public_key() {
+    let config: JwtConfig =
+        serde_json::from_value(serde_json::json!({"algorithm": "ES256"})).unwrap();
+    assert!(JWT::new(&config).is_err());
+    let config: JwtConfig =
+        serde_json::from_value(serde_json::json!({"algorithm": "XX999"})).unwrap();
+    assert!(JWT::new(&config).is_err());
+}
+
+/// 生成一个 HMAC 的新签名密钥
+fn hmac_key(kid: &str, secret: &str) -> SigningKeyConfig {
+    SigningKeyConfig {
+        algorithm: "HS256".to_string(),
+        kid: Some(kid.to_string()),
+        secret: Some(secret.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_rotated_key_still_verifies_old_tokens() {
+    let principal = Principal {
+        id: 3,
+        username: "rotating".to_string(),
+        identity: Identity::Member,
+    };
+    let now = jsonwebtoken::get_current_timestamp();
+    let old_config: JwtConfig =
+        serde_json::from_value(serde_json::json!({"kid": "k1", "secret": "secret-one"})).unwrap();
+    let old_token = JWT::new(&old_config)
+        .unwrap()
+        .encode(principal.clone())
+        .unwrap();
+
+    // 轮换后新 token 使用新的 kid，旧 token 在旧密钥过期前仍然有效
+    let config = old_config.rotate(hmac_key("k2", "secret-two"), now);
+    let jwt = JWT::new(&config).unwrap();
+    let token = jwt.encode(principal.clone()).unwrap();
+    let header = jsonwebtoken::decode_header(&token).unwrap();
+    assert_eq!(header.kid.as_deref(), Some("k2"));
+    assert_eq!(jwt.decode(&token).unwrap().id, 3);
+    assert_eq!(jwt.decode(&old_token).unwrap().id, 3);
+
+    // 旧密钥过期后不再接受它签发的 token，过期的旧密钥在下次轮换时被清理
+    let expired = old_config.rotate(hmac_key("k2", "secret-two"), now - 3600);
+    assert!(JWT::new(&expired).unwrap().decode(&old_token).is_err());
+    let pruned = expired.rotate(hmac_key("k3", "secret-three"), now);
+    assert_eq!(pruned.previous_keys().len(), 1);
+    assert_eq!(pruned.previous_keys()[0].kid(), Some("k2"));
+
+    // 未知 kid 的 token 不被接受
+    let other: JwtConfig =
+        serde_json::from_value(serde_json::json!({"kid": "k9", "secret": "secret-nine"})).unwrap();
+    let other_token = JWT::new(&other).unwrap().encode(principal).unwrap();
+    assert!(jwt.decode(&other_token).is_err());
+}
+
+#[test]
+fn test_rotated_public_keys_are_published() {
+    let now = jsonwebtoken::get_current_timestamp();
+    let fixture = |name: &str| {
+        Some(format!(
+            "file:{}/tests/fixtures/{name}.pem",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+    };
+    let config = asymmetric_config("ES256", "ec", true).rotate(
+        SigningKeyConfig {
+            algorithm: "EdDSA".to_string(),
+            kid: Some("ed-1".to_string()),
+            private_key: fixture("ed_private"),
+            public_key: fixture("ed_public"),
+            ..Default::default()
+        },
+        now,
+    );
+    let jwks = JWT::new(&config).unwrap().jwks();
+    assert_eq!(jwks.keys.len(), 2);
+    assert!(jwks.find("ed-1").is_some());
+    // 重复的 kid 无法加载
+    let duplicated = config.rotate(hmac_key("ed-1", "secret"), now);
+    assert!(JWT::new(&duplicated).is_err());
+}
+
+#[test]
+fn test_default_secret_rejected_outside_dev() {
+    let error = init_global_jwt(&JwtConfig::default(), false).unwrap_err();
+    assert!(error.to_string().contains("default jwt secret"));
+}
+
+#[test]
+fn test_shipped_prod_config_is_not_dev() {
+    // 生产配置必须关闭开发模式，否则会使用仓库中公开的默认密钥签发 token
+    let config: AppConfig = config::Config::builder()
+        .add_source(config::File::with_name("prod.yml").format(config::FileFormat::Yaml))
+        .build()
+        .unwrap()
+        .try_deserialize()
+        .unwrap();
+    assert!(!config.is_dev());
+}
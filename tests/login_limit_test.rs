@@ -0,0 +1,50 @@
+use axum::response::IntoResponse;
+use tonic::{Status, metadata::MetadataValue};
+use user_server::{conf::login_limit::LoginLimitConfig, response::errors::ApiError};
+
+#[test]
+fn test_lockout_backoff_is_exponential_and_capped() {
+    let config = LoginLimitConfig::default();
+    assert_eq!(config.lockout_secs(1), 60);
+    assert_eq!(config.lockout_secs(2), 120);
+    assert_eq!(config.lockout_secs(3), 240);
+    assert_eq!(config.lockout_secs(7), 3600);
+    assert_eq!(config.lockout_secs(u64::MAX), 3600);
+}
+
+#[test]
+fn test_resource_exhausted_maps_to_too_many_requests() {
+    let mut status = Status::resource_exhausted("登录失败次数过多");
+    status
+        .metadata_mut()
+        .insert("retry-after", MetadataValue::from(120u64));
+    let error = ApiError::from(status);
+    assert!(matches!(
+        error,
+        ApiError::TooManyRequests {
+            retry_after: Some(120),
+            ..
+        }
+    ));
+    let response = error.into_response();
+    assert_eq!(response.status(), axum::http::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        response
+            .headers()
+            .get(axum::http::header::RETRY_AFTER)
+            .unwrap(),
+        "120"
+    );
+}
+
+#[test]
+fn test_login_subject_keys_on_resolved_account() {
+    use user_server::service_impl::login_limit::login_subject;
+    // 用户名和不同大小写的邮箱都落到账号的用户名上
+    assert_eq!(login_subject(Some("bob"), "Bob@x.com"), "bob");
+    assert_eq!(login_subject(Some("bob"), "BOB@X.COM"), "bob");
+    assert_eq!(login_subject(Some("bob"), "bob"), "bob");
+    // 账号不存在时邮箱统一转为小写
+    assert_eq!(login_subject(None, " Bob@X.com "), "bob@x.com");
+    assert_eq!(login_subject(None, "Alice"), "Alice");
+}
@@ -0,0 +1,73 @@
+use axum::response::IntoResponse;
+use user_server::{
+    conf::mail::MailConfig,
+    mail::{Mail, build_mailer},
+    response::errors::ApiError,
+    service_impl::password_reset::too_many_requests,
+};
+
+/// 使用 json 构造邮件配置
+fn mail_config(value: serde_json::Value) -> MailConfig {
+    serde_json::from_value(value).unwrap()
+}
+
+#[tokio::test]
+async fn test_file_mailer_writes_eml() {
+    let dir = std::env::temp_dir().join(format!("mails_{}", std::process::id()));
+    let config = mail_config(serde_json::json!({
+        "transport": "file",
+        "file_dir": dir.to_str().unwrap(),
+    }));
+    let mailer = build_mailer(&config).unwrap();
+    mailer
+        .send(Mail {
+            to: "tester@example.com".to_string(),
+            subject: "Reset password".to_string(),
+            body: "open http://localhost/reset?token=abc".to_string(),
+        })
+        .await
+        .unwrap();
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "eml"))
+        .collect();
+    assert_eq!(files.len(), 1);
+    let content = std::fs::read_to_string(&files[0]).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(content.contains("To: tester@example.com"));
+    assert!(content.contains("Subject: Reset password"));
+    assert!(content.contains("token=abc"));
+}
+
+#[test]
+fn test_build_mailer_rejects_invalid_config() {
+    assert!(build_mailer(&MailConfig::default()).is_ok());
+    // smtp 方式必须配置服务器
+    let config = mail_config(serde_json::json!({"transport": "smtp"}));
+    assert!(build_mailer(&config).is_err());
+    // 发件人地址必须合法
+    let config = mail_config(serde_json::json!({"from": "not an address"}));
+    assert!(build_mailer(&config).is_err());
+}
+
+#[test]
+fn test_password_reset_throttle_maps_to_too_many_requests() {
+    let error = ApiError::from(too_many_requests(300));
+    assert!(matches!(
+        error,
+        ApiError::TooManyRequests {
+            retry_after: Some(300),
+            ..
+        }
+    ));
+    let response = error.into_response();
+    assert_eq!(response.status(), axum::http::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        response
+            .headers()
+            .get(axum::http::header::RETRY_AFTER)
+            .unwrap(),
+        "300"
+    );
+}
@@ -0,0 +1,57 @@
+use user_server::handlers::common::model::{
+    ChangePasswordParam, LoginUserParam, RegisterUserParam,
+};
+use validator::Validate;
+
+#[test]
+fn test_change_password_param_validation() {
+    let param: ChangePasswordParam = serde_json::from_value(serde_json::json!({
+        "currentPassword": "old-password",
+        "newPassword": "new-password",
+    }))
+    .unwrap();
+    assert!(param.validate().is_ok());
+    // 密码强度由服务端的密码策略检查，这里只拒绝空密码
+    let param: ChangePasswordParam = serde_json::from_value(serde_json::json!({
+        "currentPassword": "old-password",
+        "newPassword": "",
+    }))
+    .unwrap();
+    assert!(param.validate().is_err());
+}
+
+#[test]
+fn test_register_param_email_validation() {
+    // 邮箱可以不填
+    let param: RegisterUserParam = serde_json::from_value(serde_json::json!({
+        "username": "tester",
+        "password": "password",
+    }))
+    .unwrap();
+    assert!(param.validate().is_ok());
+    assert!(param.email.is_none());
+    let param: RegisterUserParam = serde_json::from_value(serde_json::json!({
+        "username": "tester",
+        "password": "password",
+        "email": "tester@example.com",
+    }))
+    .unwrap();
+    assert!(param.validate().is_ok());
+    let param: RegisterUserParam = serde_json::from_value(serde_json::json!({
+        "username": "tester",
+        "password": "password",
+        "email": "not-an-email",
+    }))
+    .unwrap();
+    assert!(param.validate().is_err());
+}
+
+#[test]
+fn test_login_param_accepts_email() {
+    let param: LoginUserParam = serde_json::from_value(serde_json::json!({
+        "username": "a-rather-long-address@example.com",
+        "password": "password",
+    }))
+    .unwrap();
+    assert!(param.validate().is_ok());
+}
@@ -0,0 +1,106 @@
+use user_server::{
+    response::errors::ApiError,
+    service_impl::password_policy::{PasswordPolicy, PolicyViolation, estimate_entropy},
+};
+
+fn build_policy(value: serde_json::Value) -> PasswordPolicy {
+    PasswordPolicy::new(serde_json::from_value(value).unwrap()).unwrap()
+}
+
+#[test]
+fn test_default_policy_accepts_passphrase() {
+    let policy = build_policy(serde_json::json!({}));
+    assert!(
+        policy
+            .check("correct horse battery staple", "tester")
+            .is_empty()
+    );
+    assert!(policy.check("Tr0ub4dor&3", "tester").is_empty());
+}
+
+#[test]
+fn test_check_reports_every_violation() {
+    let policy = build_policy(serde_json::json!({
+        "require_uppercase": true,
+        "require_digit": true,
+        "require_symbol": true,
+        "min_character_classes": 3,
+    }));
+    let violations = policy.check("aaaa", "tester");
+    assert_eq!(
+        violations,
+        vec![
+            PolicyViolation::TooShort { min: 8 },
+            PolicyViolation::MissingUppercase,
+            PolicyViolation::MissingDigit,
+            PolicyViolation::MissingSymbol,
+            PolicyViolation::TooFewCharacterClasses { min: 3 },
+            PolicyViolation::LowEntropy { min_bits: 36.0 },
+        ]
+    );
+}
+
+#[test]
+fn test_check_rejects_username() {
+    let policy = build_policy(serde_json::json!({}));
+    assert!(
+        policy
+            .check("my-Alice-password", "alice")
+            .contains(&PolicyViolation::ContainsUsername)
+    );
+    // 太短的用户名不检查
+    assert!(
+        !policy
+            .check("my-al-password", "al")
+            .contains(&PolicyViolation::ContainsUsername)
+    );
+    let relaxed = build_policy(serde_json::json!({"reject_username": false}));
+    assert!(relaxed.check("my-alice-password", "alice").is_empty());
+}
+
+#[test]
+fn test_check_rejects_blocklisted_password() {
+    let path = std::env::temp_dir().join(format!("blocklist_{}.txt", std::process::id()));
+    std::fs::write(&path, "# 注释\nCorrectHorse99\n\n").unwrap();
+    let policy = build_policy(serde_json::json!({"blocklist_file": path.to_str().unwrap()}));
+    assert_eq!(
+        policy.check("correcthorse99", "tester"),
+        vec![PolicyViolation::Blocklisted]
+    );
+    assert!(policy.check("correcthorse98", "tester").is_empty());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_new_rejects_invalid_config() {
+    let config = serde_json::from_value(serde_json::json!({"min_length": 20, "max_length": 10}));
+    assert!(PasswordPolicy::new(config.unwrap()).is_err());
+    let config = serde_json::from_value(serde_json::json!({"blocklist_file": "/nonexistent/list"}));
+    assert!(PasswordPolicy::new(config.unwrap()).is_err());
+}
+
+#[test]
+fn test_estimate_entropy_penalizes_sequences() {
+    assert!(estimate_entropy("12345678") < estimate_entropy("19283746"));
+    assert!(estimate_entropy("aaaaaaaa") < 36.0);
+    assert!(estimate_entropy("correct horse battery staple") > 100.0);
+    assert_eq!(estimate_entropy(""), 0.0);
+}
+
+#[test]
+fn test_validate_maps_to_invalid_fields() {
+    let policy = build_policy(serde_json::json!({}));
+    assert!(policy.validate("password", "Tr0ub4dor&3", "tester").is_ok());
+    let status = policy
+        .validate("new_password", "short", "tester")
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    match ApiError::from(status) {
+        ApiError::InvalidFields { fields, .. } => {
+            let reasons = fields.iter().map(|f| f.reason.as_str()).collect::<Vec<_>>();
+            assert_eq!(reasons, vec!["PASSWORD_TOO_SHORT", "PASSWORD_TOO_WEAK"]);
+            assert!(fields.iter().all(|f| f.field == "new_password"));
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use std::time::Duration;
+
+use tonic::Code;
+use user_server::service_impl::refresh_token;
+
+/// 测试令牌的有效期
+const TTL: Duration = Duration::from_secs(3600);
+
+#[tokio::test]
+async fn test_rotate_issues_new_token_in_same_family() {
+    let Some(pool) = common::test_pool().await else {
+        return;
+    };
+    let user_id = common::insert_user(&pool, "rotate").await;
+    let token = refresh_token::issue(&pool, user_id, "family-rotate", TTL)
+        .await
+        .unwrap();
+
+    let rotated = refresh_token::rotate(&pool, &token, TTL).await.unwrap();
+    assert_eq!(rotated.user_id, user_id);
+    assert_eq!(rotated.family_id, "family-rotate");
+    assert_ne!(rotated.token, token);
+    // 新令牌可以继续轮换
+    let again = refresh_token::rotate(&pool, &rotated.token, TTL)
+        .await
+        .unwrap();
+    assert_eq!(again.family_id, "family-rotate");
+}
+
+#[tokio::test]
+async fn test_reused_token_revokes_whole_family() {
+    let Some(pool) = common::test_pool().await else {
+        return;
+    };
+    let user_id = common::insert_user(&pool, "reuse").await;
+    let token = refresh_token::issue(&pool, user_id, "family-reuse", TTL)
+        .await
+        .unwrap();
+    let other = refresh_token::issue(&pool, user_id, "family-other", TTL)
+        .await
+        .unwrap();
+    let rotated = refresh_token::rotate(&pool, &token, TTL).await.unwrap();
+
+    // 再次提交已经轮换过的令牌，视为泄露
+    let error = refresh_token::rotate(&pool, &token, TTL).await.unwrap_err();
+    assert_eq!(error.code(), Code::Unauthenticated);
+    // 同一令牌族中最新的令牌也被吊销
+    let error = refresh_token::rotate(&pool, &rotated.token, TTL)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), Code::Unauthenticated);
+    // 其他令牌族不受影响
+    refresh_token::rotate(&pool, &other, TTL).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_expired_token_is_rejected() {
+    let Some(pool) = common::test_pool().await else {
+        return;
+    };
+    let user_id = common::insert_user(&pool, "expired").await;
+    let token = refresh_token::issue(&pool, user_id, "family-expired", Duration::ZERO)
+        .await
+        .unwrap();
+
+    let error = refresh_token::rotate(&pool, &token, TTL).await.unwrap_err();
+    assert_eq!(error.code(), Code::Unauthenticated);
+    assert!(
+        refresh_token::rotate(&pool, "unknown-token", TTL)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_logout_revokes_family() {
+    let Some(pool) = common::test_pool().await else {
+        return;
+    };
+    let user_id = common::insert_user(&pool, "logout").await;
+    let other_user = common::insert_user(&pool, "logout-other").await;
+    let token = refresh_token::issue(&pool, user_id, "family-logout", TTL)
+        .await
+        .unwrap();
+    let rotated = refresh_token::rotate(&pool, &token, TTL).await.unwrap();
+
+    // 不能吊销其他用户的令牌
+    refresh_token::revoke_by_token(&pool, &rotated.token, other_user)
+        .await
+        .unwrap();
+    let rotated = refresh_token::rotate(&pool, &rotated.token, TTL)
+        .await
+        .unwrap();
+
+    // 退出登录后整个令牌族失效
+    refresh_token::revoke_by_token(&pool, &rotated.token, user_id)
+        .await
+        .unwrap();
+    let error = refresh_token::rotate(&pool, &rotated.token, TTL)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), Code::Unauthenticated);
+}
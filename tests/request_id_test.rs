@@ -0,0 +1,103 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    middleware,
+};
+use tonic::service::Interceptor;
+use tower::ServiceExt;
+use user_server::{
+    middlewares::request_id::http_request_id::{parse_request_id, set_request_id},
+    response::{ApiResult, errors::ApiError},
+    utils::trace::TraceContextInterceptor,
+};
+
+fn app() -> axum::Router {
+    axum::Router::new()
+        .route(
+            "/fail",
+            axum::routing::get(async || -> ApiResult<()> { Err(ApiError::NotFound) }),
+        )
+        .route(
+            "/forward",
+            axum::routing::get(async || {
+                // 调用 gRPC 服务时拦截器会带上当前的请求 id
+                let request = TraceContextInterceptor
+                    .call(tonic::Request::new(()))
+                    .unwrap();
+                request
+                    .metadata()
+                    .get("x-request-id")
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            }),
+        )
+        .layer(middleware::from_fn(set_request_id))
+}
+
+async fn send(uri: &str, request_id: Option<&str>) -> (StatusCode, String, String) {
+    let mut request = Request::get(uri);
+    if let Some(request_id) = request_id {
+        request = request.header("x-request-id", request_id);
+    }
+    let response = app()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let header = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, header, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_error_body_echoes_client_request_id() {
+    let (status, header, body) = send("/fail", Some("client-42")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(header, "client-42");
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["request_id"], "client-42");
+    assert_eq!(body["error"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_request_id_is_generated_when_missing_or_invalid() {
+    let (_, header, body) = send("/fail", None).await;
+    assert_eq!(header.len(), 20);
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["request_id"], header.as_str());
+
+    let (_, header, _) = send("/fail", Some("bad id\twith spaces")).await;
+    assert_ne!(header, "bad id\twith spaces");
+    assert_eq!(header.len(), 20);
+}
+
+#[tokio::test]
+async fn test_request_id_is_forwarded_to_grpc_metadata() {
+    let (status, header, body) = send("/forward", Some("abc-123")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(header, "abc-123");
+    assert_eq!(body, "abc-123");
+}
+
+#[test]
+fn test_parse_request_id() {
+    assert_eq!(
+        parse_request_id(" 9m4e2mr0ui3e8a215n4g "),
+        Some("9m4e2mr0ui3e8a215n4g")
+    );
+    assert_eq!(
+        parse_request_id("f47ac10b-58cc-4372-a567-0e02b2c3d479"),
+        Some("f47ac10b-58cc-4372-a567-0e02b2c3d479")
+    );
+    assert_eq!(parse_request_id(""), None);
+    assert_eq!(parse_request_id("a b"), None);
+    assert_eq!(parse_request_id("<script>"), None);
+    assert_eq!(parse_request_id(&"a".repeat(65)), None);
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use user_server::middlewares::auth::{
+    identity::Identity,
+    jwt::{Claims, JWT, current_timestamp_millis},
+    principal::Principal,
+    revocation::issued_before,
+};
+
+/// 签发一个 token 并解析出 Claims
+fn issue_claims(jwt: &JWT) -> Claims {
+    let token = jwt
+        .encode(Principal {
+            id: 7,
+            username: "tester".to_string(),
+            identity: Identity::Guest,
+        })
+        .unwrap();
+    jwt.decode_claims(&token).unwrap()
+}
+
+#[tokio::test]
+async fn test_session_revocation_uses_millisecond_precision() {
+    let jwt = JWT::default();
+    // 退出全部会话前签发的 token，与吊销时间通常在同一秒内
+    let old = issue_claims(&jwt);
+    assert_eq!(old.iat(), old.iat_millis() / 1000);
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let revoked_before = current_timestamp_millis();
+    // 修改密码后紧接着签发的新 token
+    let new = issue_claims(&jwt);
+
+    assert!(issued_before(&old, Some(revoked_before)));
+    assert!(!issued_before(&new, Some(revoked_before)));
+    assert!(!issued_before(&old, None));
+}
+
+#[test]
+fn test_tokens_without_iat_ms_fall_back_to_seconds() {
+    let claims: Claims = serde_json::from_value(serde_json::json!({
+        "jti": "jti",
+        "sub": "7:tester:guest",
+        "aud": "audience",
+        "iss": "issuer",
+        "iat": 1_700_000_000u64,
+        "exp": 1_700_000_900u64,
+    }))
+    .unwrap();
+    assert_eq!(claims.iat_millis(), 1_700_000_000_000);
+    assert!(issued_before(&claims, Some(1_700_000_000_001)));
+    assert!(!issued_before(&claims, Some(1_700_000_000_000)));
+}
@@ -0,0 +1,96 @@
+use opentelemetry::trace::{TraceContextExt, Tracer, TracerProvider as _};
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use user_server::{
+    conf::otel::OtelConfig,
+    db::trace::db_span,
+    log::otel::{FileSpanExporter, init_tracer_provider},
+    utils::trace::{inject_context, make_grpc_span, make_http_span},
+};
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[test]
+fn test_traceparent_propagates_from_http_to_grpc() {
+    // 默认配置不导出，只设置传播器
+    init_tracer_provider(&OtelConfig::default(), "user-server-test").unwrap();
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    // HTTP 服务：继续客户端的链路，并把上下文写入 gRPC metadata
+    let request = http::Request::post("/api/v1/user/login")
+        .header("traceparent", TRACEPARENT)
+        .body(())
+        .unwrap();
+    let http_span = make_http_span(&request);
+    let http_span_id = http_span.context().span().span_context().span_id();
+    let mut metadata = tonic::metadata::MetadataMap::new();
+    http_span.in_scope(|| inject_context(&mut metadata));
+    let traceparent = metadata.get("traceparent").unwrap().to_str().unwrap();
+    assert!(traceparent.contains(TRACE_ID));
+    assert!(traceparent.contains(&http_span_id.to_string()));
+
+    // gRPC 服务：从 metadata 继续同一条链路，查询数据库的 span 是它的子 span
+    let mut request = http::Request::post("/user.UserService/UserLogin")
+        .body(())
+        .unwrap();
+    *request.headers_mut() = metadata.into_headers();
+    let grpc_span = make_grpc_span(&request);
+    grpc_span.in_scope(|| drop(db_span("SELECT", "user")));
+    drop(grpc_span);
+    drop(http_span);
+
+    let spans = exporter.get_finished_spans().unwrap();
+    let find = |name: &str| spans.iter().find(|span| span.name == name).unwrap();
+    let http = find("POST /api/v1/user/login").span_context.clone();
+    let grpc = find("user.UserService/UserLogin");
+    let db = find("SELECT user");
+    assert_eq!(http.trace_id().to_string(), TRACE_ID);
+    assert_eq!(grpc.span_context.trace_id().to_string(), TRACE_ID);
+    assert_eq!(grpc.parent_span_id, http.span_id());
+    assert_eq!(db.parent_span_id, grpc.span_context.span_id());
+}
+
+#[test]
+fn test_grpc_span_without_traceparent_starts_new_trace() {
+    init_tracer_provider(&OtelConfig::default(), "user-server-test").unwrap();
+    let provider = SdkTracerProvider::builder().build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    let _default = tracing::subscriber::set_default(subscriber);
+
+    let request = http::Request::post("/user.UserService/UserLogin")
+        .body(())
+        .unwrap();
+    let span_context = make_grpc_span(&request)
+        .context()
+        .span()
+        .span_context()
+        .clone();
+    assert!(span_context.is_valid());
+    assert_ne!(span_context.trace_id().to_string(), TRACE_ID);
+}
+
+#[test]
+fn test_file_exporter_writes_json_lines() {
+    let path = std::env::temp_dir().join(format!("spans_{}.jsonl", std::process::id()));
+    let exporter = FileSpanExporter::new(path.to_str().unwrap()).unwrap();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter)
+        .build();
+    provider.tracer("test").in_span("SELECT user", |_| {});
+    provider.shutdown().unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let line: serde_json::Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+    assert_eq!(line["name"], "SELECT user");
+    assert_eq!(line["trace_id"].as_str().unwrap().len(), 32);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,53 @@
+use tonic::Code;
+use user_server::{
+    middlewares::auth::identity::Identity, pb::user::ListUsersRequest,
+    service_impl::user_admin::UserFilter,
+};
+
+#[test]
+fn test_user_filter_defaults_and_caps() {
+    let filter = UserFilter::try_from(ListUsersRequest::default()).unwrap();
+    assert_eq!(filter.page, 1);
+    assert_eq!(filter.page_size, 20);
+    assert!(filter.level.is_none());
+    assert!(filter.username_prefix.is_none());
+
+    let filter = UserFilter::try_from(ListUsersRequest {
+        page: 3,
+        page_size: 1000,
+        level: Some("VIP".to_string()),
+        is_open: Some(false),
+        created_after: Some("2026-01-01T00:00:00+08:00".to_string()),
+        username_prefix: Some(String::new()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(filter.page, 3);
+    assert_eq!(filter.page_size, 100);
+    assert_eq!(filter.level, Some(Identity::Vip));
+    assert_eq!(filter.is_open, Some(false));
+    assert_eq!(
+        filter.created_after.unwrap().to_rfc3339(),
+        "2025-12-31T16:00:00+00:00"
+    );
+    // 空前缀视为不过滤
+    assert!(filter.username_prefix.is_none());
+}
+
+#[test]
+fn test_user_filter_rejects_invalid_values() {
+    let error = UserFilter::try_from(ListUsersRequest {
+        level: Some("root".to_string()),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert_eq!(error.code(), Code::InvalidArgument);
+    let error = UserFilter::try_from(ListUsersRequest {
+        created_before: Some("yesterday".to_string()),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert_eq!(error.code(), Code::InvalidArgument);
+    assert!(Identity::parse("root").is_none());
+    assert_eq!(Identity::parse("Admin"), Some(Identity::Admin));
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{Extension, extract::State};
+use sqlx::PgPool;
+use tonic::{
+    Code,
+    transport::{Server, server::TcpIncoming},
+};
+use user_server::{
+    conf::{
+        enumeration::EnumerationConfig, login_limit::LoginLimitConfig, mail::MailConfig,
+        password_policy::PasswordPolicyConfig, register::RegisterConfig,
+    },
+    factory::client::authorized_request,
+    handlers::me::profile::get_profile_handler,
+    mail::log::LogMailer,
+    middlewares::auth::{
+        auth_layer::AccessToken, grpc_auth::GrpcAuthLayer, identity::Identity, jwt::JWT,
+        principal::Principal,
+    },
+    pb::user::{GetUserRequest, user_service_server::UserServiceServer},
+    service_impl::{
+        login_limit::LoginLimiter, password_policy::PasswordPolicy, user::UserServiceImpl,
+    },
+    state::app_state::AppState,
+};
+
+static JWT_FOR_TEST: std::sync::LazyLock<JWT> = std::sync::LazyLock::new(JWT::default);
+
+/// 在随机端口启动带认证层的 gRPC 服务，返回 HTTP 服务使用的状态
+async fn start_grpc_server(pool: PgPool) -> AppState {
+    let pool: &'static PgPool = Box::leak(Box::new(pool));
+    let srv = UserServiceImpl::new(
+        pool,
+        LoginLimiter::new(LoginLimitConfig::default()),
+        Arc::new(LogMailer),
+        MailConfig::default(),
+        RegisterConfig::default(),
+        PasswordPolicy::new(PasswordPolicyConfig::default()).unwrap(),
+        EnumerationConfig::default(),
+    );
+    let incoming = TcpIncoming::bind(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+    let addr = incoming.local_addr().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .layer(GrpcAuthLayer::new(&JWT_FOR_TEST))
+            .add_service(UserServiceServer::new(srv))
+            .serve_with_incoming(incoming),
+    );
+    AppState::new(&format!("http://{addr}")).await.unwrap()
+}
+
+/// 签发测试用户的 access token
+fn access_token(id: i32, username: &str, identity: Identity) -> (Principal, AccessToken) {
+    let principal = Principal {
+        id,
+        username: username.to_string(),
+        identity,
+    };
+    let token = JWT_FOR_TEST.encode(principal.clone()).unwrap();
+    (principal, AccessToken(token))
+}
+
+#[tokio::test]
+async fn test_me_returns_own_profile() {
+    // 认证层会检查 access token 是否已被吊销
+    if !common::init_test_redis().await {
+        return;
+    }
+    let Some(pool) = common::test_pool().await else {
+        return;
+    };
+    common::insert_user(&pool, "someone-else").await;
+    let user_id = common::insert_user(&pool, "me").await;
+    let state = start_grpc_server(pool).await;
+
+    let (principal, token) = access_token(user_id, "me", Identity::Member);
+    let response = get_profile_handler(State(state), Extension(principal), Extension(token))
+        .await
+        .unwrap();
+    let profile = response.data.unwrap();
+    assert_eq!(profile.id, user_id);
+    assert_eq!(profile.username, "me");
+}
+
+#[tokio::test]
+async fn test_get_user_is_limited_to_self_or_admin() {
+    // 认证层会检查 access token 是否已被吊销
+    if !common::init_test_redis().await {
+        return;
+    }
+    let Some(pool) = common::test_pool().await else {
+        return;
+    };
+    let user_id = common::insert_user(&pool, "member").await;
+    let other_id = common::insert_user(&pool, "other").await;
+    let state = start_grpc_server(pool).await;
+    let mut client = state.grpc_factory.create_client().await.unwrap();
+
+    // 普通用户只能查询自己
+    let (_, token) = access_token(user_id, "member", Identity::Member);
+    let request = authorized_request(GetUserRequest { user_id }, &token).unwrap();
+    assert_eq!(
+        client.get_user(request).await.unwrap().into_inner().id,
+        user_id
+    );
+    let request = authorized_request(GetUserRequest { user_id: other_id }, &token).unwrap();
+    let status = client.get_user(request).await.unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    // 管理员可以查询其他用户
+    let (_, token) = access_token(user_id, "member", Identity::Admin);
+    let request = authorized_request(GetUserRequest { user_id: other_id }, &token).unwrap();
+    assert_eq!(
+        client
+            .get_user(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .username,
+        "other"
+    );
+}